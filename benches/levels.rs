@@ -0,0 +1,70 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use google_hashcode_score_2022::data::Id;
+use google_hashcode_score_2022::levels::{HashLevelMap, Level, LevelMap};
+use google_hashcode_score_2022::score::PreComputed;
+use google_hashcode_score_2022::stream::precompute_from_reader;
+use std::fs::{read_dir, File};
+use std::io::BufReader;
+
+fn datasets() -> Vec<(String, PreComputed)> {
+    let mut paths: Vec<_> = read_dir("res")
+        .expect("res directory")
+        .map(|e| e.expect("dir entry").path())
+        .filter(|p| p.extension().is_some_and(|e| e == "txt"))
+        .collect();
+    paths.sort();
+    paths
+        .iter()
+        .map(|p| {
+            let name = p.file_name().unwrap().to_string_lossy().into_owned();
+            let reader = BufReader::new(File::open(p).expect("input file"));
+            (name, precompute_from_reader(reader).expect("valid input"))
+        })
+        .collect()
+}
+
+/// (contributor id, skill id) lookups in the same pattern as `check_contributors_level`:
+/// for each project, every role skill is looked up for every contributor of the team.
+fn lookups(precomputed: &PreComputed) -> Vec<(Id, Id)> {
+    let n_contributors = precomputed.contributors.len();
+    let mut lookups = vec![];
+    for project in &precomputed.projects {
+        let team: Vec<Id> = (0..project.skills.len())
+            .map(|k| (project.id * 7 + k) % n_contributors)
+            .collect();
+        for (skill, _) in &project.skills {
+            for contributor in &team {
+                lookups.push((*contributor, *skill));
+            }
+        }
+    }
+    lookups
+}
+
+fn sum_levels(lookups: &[(Id, Id)], get: impl Fn(&(Id, Id)) -> Option<Level>) -> Level {
+    lookups.iter().map(|key| get(key).unwrap_or(0)).sum()
+}
+
+fn bench_levels(c: &mut Criterion) {
+    let mut group = c.benchmark_group("levels_lookup");
+    for (name, precomputed) in datasets() {
+        let lookups = lookups(&precomputed);
+        let per_contributor: &LevelMap = &precomputed.levels;
+        let hash: HashLevelMap = per_contributor
+            .iter()
+            .map(|(key, level)| (key, *level))
+            .collect();
+        group.bench_with_input(
+            BenchmarkId::new("per_contributor", &name),
+            &lookups,
+            |b, l| b.iter(|| sum_levels(black_box(l), |key| per_contributor.get(key).cloned())),
+        );
+        group.bench_with_input(BenchmarkId::new("fxhash", &name), &lookups, |b, l| {
+            b.iter(|| sum_levels(black_box(l), |key| hash.get(key).cloned()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_levels);
+criterion_main!(benches);
@@ -0,0 +1,142 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use google_hashcode_score_2022::data::{PInput, POutput};
+use google_hashcode_score_2022::parser::{parse_input, parse_output};
+use google_hashcode_score_2022::score::{
+    compute_score_precomputed, decode_precomputed, encode_precomputed, precompute_from_input,
+};
+use google_hashcode_score_2022::stream::precompute_from_reader;
+use std::fmt::Write;
+use std::fs::{read_dir, read_to_string};
+use std::path::{Path, PathBuf};
+
+struct Dataset {
+    name: String,
+    input_content: String,
+    // provided output (out/<name>.out) if any, scored with checks enabled
+    output_content: Option<String>,
+}
+
+fn datasets() -> Vec<Dataset> {
+    let mut paths: Vec<PathBuf> = read_dir("res")
+        .expect("res directory")
+        .map(|e| e.expect("dir entry").path())
+        .filter(|p| p.extension().is_some_and(|e| e == "txt"))
+        .collect();
+    paths.sort();
+    paths
+        .iter()
+        .map(|p| {
+            let name = p.file_name().unwrap().to_string_lossy().into_owned();
+            let output_path = Path::new("out").join(format!("{}.out", name));
+            Dataset {
+                input_content: read_to_string(p).expect("input file"),
+                output_content: read_to_string(output_path).ok(),
+                name,
+            }
+        })
+        .collect()
+}
+
+/// Plans every project with the first contributors (round robin), not a valid
+/// submission but it exercises the whole simulation when checks are disabled.
+fn round_robin_output(input: &PInput) -> String {
+    let mut output = format!("{}\n", input.projects.len());
+    let mut next = 0;
+    for project in &input.projects {
+        let names: Vec<&str> = (0..project.n_roles)
+            .map(|_| {
+                next = (next + 1) % input.contributors.len();
+                input.contributors[next].name.as_str()
+            })
+            .collect();
+        let _ = writeln!(output, "{}\n{}", project.name, names.join(" "));
+    }
+    output
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    group.sample_size(10);
+    for dataset in datasets() {
+        let input = parse_input(&dataset.input_content).unwrap();
+        let output_content = round_robin_output(&input);
+        group.bench_with_input(
+            BenchmarkId::new("parse_input", &dataset.name),
+            &dataset.input_content,
+            |b, s| b.iter(|| parse_input(s).unwrap()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("parse_output", &dataset.name),
+            &output_content,
+            |b, s| b.iter(|| parse_output(s).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+fn bench_precompute(c: &mut Criterion) {
+    let mut group = c.benchmark_group("precompute");
+    group.sample_size(10);
+    let cache_dir = std::env::temp_dir();
+    for dataset in datasets() {
+        let input = parse_input(&dataset.input_content).unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("precompute_from_input", &dataset.name),
+            &input,
+            |b, input| b.iter(|| precompute_from_input(input)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("precompute_from_reader", &dataset.name),
+            &dataset.input_content,
+            |b, s| b.iter(|| precompute_from_reader(s.as_bytes()).unwrap()),
+        );
+        let bin_path = cache_dir.join(format!("{}.bench.bin", dataset.name));
+        encode_precomputed(&precompute_from_input(&input), &bin_path).unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("decode_precomputed", &dataset.name),
+            &bin_path,
+            |b, path| b.iter(|| decode_precomputed(path).unwrap()),
+        );
+        let _ = std::fs::remove_file(bin_path);
+    }
+    group.finish();
+}
+
+fn bench_score(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compute_score_precomputed");
+    group.sample_size(10);
+    for dataset in datasets() {
+        let input = parse_input(&dataset.input_content).unwrap();
+        let precomputed = precompute_from_input(&input);
+        let round_robin: POutput = parse_output(&round_robin_output(&input)).unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("unchecked", &dataset.name),
+            &round_robin,
+            |b, output| {
+                b.iter_batched(
+                    || precomputed.clone(),
+                    |mut p| compute_score_precomputed(&mut p, output, true).unwrap(),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+        if let Some(output_content) = &dataset.output_content {
+            let output = parse_output(output_content).unwrap();
+            group.bench_with_input(
+                BenchmarkId::new("checked", &dataset.name),
+                &output,
+                |b, output| {
+                    b.iter_batched(
+                        || precomputed.clone(),
+                        |mut p| compute_score_precomputed(&mut p, output, false).unwrap(),
+                        BatchSize::LargeInput,
+                    )
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_precompute, bench_score);
+criterion_main!(benches);
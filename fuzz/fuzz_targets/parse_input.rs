@@ -0,0 +1,14 @@
+#![no_main]
+use google_hashcode_score_2022::parser::parse_input;
+use google_hashcode_score_2022::score::precompute_from_input;
+use google_hashcode_score_2022::stream::precompute_from_reader;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        if let Ok(input) = parse_input(s) {
+            precompute_from_input(&input);
+        }
+    }
+    let _ = precompute_from_reader(data);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+use google_hashcode_score_2022::parser::parse_output;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        let _ = parse_output(s);
+    }
+});
@@ -0,0 +1,23 @@
+#![no_main]
+use google_hashcode_score_2022::parser::parse_output;
+use google_hashcode_score_2022::score::compute_score_precomputed;
+use google_hashcode_score_2022::stream::precompute_from_reader;
+use libfuzzer_sys::fuzz_target;
+
+// input file and output file, separated by a NUL byte
+fuzz_target!(|data: &[u8]| {
+    let mut files = data.splitn(2, |b| *b == 0);
+    let (input, output) = match (files.next(), files.next()) {
+        (Some(input), Some(output)) => (input, output),
+        _ => return,
+    };
+    let output = match std::str::from_utf8(output).map(parse_output) {
+        Ok(Ok(output)) => output,
+        _ => return,
+    };
+    if let Ok(precomputed) = precompute_from_reader(input) {
+        for disable_checks in [false, true] {
+            let _ = compute_score_precomputed(&mut precomputed.clone(), &output, disable_checks);
+        }
+    }
+});
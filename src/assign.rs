@@ -0,0 +1,328 @@
+use crate::data::{Id, POutput, PPlannedProject};
+use crate::score::{is_mentored, simulate, Level, PreComputed};
+use anyhow::bail;
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Objective {
+    /// minimize the sum of levels above the required ones
+    LeastWaste,
+    /// maximize the number of contributors who level up, then least waste
+    MostLevelUps,
+}
+
+impl FromStr for Objective {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "waste" => Ok(Objective::LeastWaste),
+            "level-ups" => Ok(Objective::MostLevelUps),
+            _ => bail!("unknown objective {:?}, expected waste or level-ups", s),
+        }
+    }
+}
+
+const FORBIDDEN: i64 = 1 << 40;
+
+/// Minimum cost assignment of every row to a distinct column (Hungarian algorithm),
+/// `None` if `costs` has more rows than columns or a row can only get a forbidden column.
+fn min_cost_assignment(costs: &[Vec<i64>]) -> Option<Vec<usize>> {
+    let n = costs.len();
+    let m = costs.first().map_or(0, |row| row.len());
+    if n > m {
+        return None;
+    }
+    // potentials and matching are 1-indexed, column 0 is a virtual column
+    let mut u = vec![0i64; n + 1];
+    let mut v = vec![0i64; m + 1];
+    let mut row_of = vec![0usize; m + 1]; // column -> matched row, 0 if free
+    let mut way = vec![0usize; m + 1];
+    for row in 1..=n {
+        row_of[0] = row;
+        let mut column = 0;
+        let mut min_slack = vec![i64::MAX; m + 1];
+        let mut used = vec![false; m + 1];
+        loop {
+            used[column] = true;
+            let current_row = row_of[column];
+            let mut delta = i64::MAX;
+            let mut next_column = 0;
+            for j in 1..=m {
+                if !used[j] {
+                    let slack = costs[current_row - 1][j - 1] - u[current_row] - v[j];
+                    if slack < min_slack[j] {
+                        min_slack[j] = slack;
+                        way[j] = column;
+                    }
+                    if min_slack[j] < delta {
+                        delta = min_slack[j];
+                        next_column = j;
+                    }
+                }
+            }
+            for j in 0..=m {
+                if used[j] {
+                    u[row_of[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min_slack[j] -= delta;
+                }
+            }
+            column = next_column;
+            if row_of[column] == 0 {
+                break;
+            }
+        }
+        // augment along the alternating path
+        while column != 0 {
+            let previous = way[column];
+            row_of[column] = row_of[previous];
+            column = previous;
+        }
+    }
+    let mut assignment = vec![0; n];
+    for j in 1..=m {
+        if row_of[j] != 0 {
+            if costs[row_of[j] - 1][j - 1] >= FORBIDDEN {
+                return None;
+            }
+            assignment[row_of[j] - 1] = j - 1;
+        }
+    }
+    Some(assignment)
+}
+
+/// Valid assignment of `available` contributors to the roles of `project`, contributor id
+/// for each role, `None` if there is none.
+///
+/// Levels are read from `precomputed`. A contributor one level short is only kept when
+/// another contributor of the assignment can mentor them: mentored pairs without a mentor
+/// are forbidden and the matching is computed again, so a valid assignment relying on
+/// mentoring may be missed. The assignment is optimal for `objective` whenever the best one
+/// needs no mentoring.
+pub fn assign_roles(
+    precomputed: &PreComputed,
+    project: Id,
+    available: &[Id],
+    objective: Objective,
+) -> Option<Vec<Id>> {
+    let roles = &precomputed.projects.get(project)?.skills;
+    let level = |contributor: Id, skill: Id| {
+        precomputed
+            .levels
+            .get(&(contributor, skill))
+            .cloned()
+            .unwrap_or(0)
+    };
+    let mut candidates: Vec<Id> = available
+        .iter()
+        .cloned()
+        .filter(|c| {
+            roles
+                .iter()
+                .any(|(skill, required)| level(*c, *skill).saturating_add(1) >= *required)
+        })
+        .collect();
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    let waste = |own: Level, required: Level| own.saturating_sub(required) as i64;
+    let max_waste = candidates
+        .iter()
+        .flat_map(|c| {
+            roles
+                .iter()
+                .map(move |(skill, required)| waste(level(*c, *skill), *required))
+        })
+        .max()
+        .unwrap_or(0);
+    // one more level up is worth more than any amount of waste
+    let level_up_weight = max_waste * roles.len() as i64 + 1;
+    let mut costs: Vec<Vec<i64>> = roles
+        .iter()
+        .map(|(skill, required)| {
+            candidates
+                .iter()
+                .map(|c| {
+                    let own = level(*c, *skill);
+                    if own.saturating_add(1) < *required {
+                        FORBIDDEN
+                    } else {
+                        match objective {
+                            Objective::LeastWaste => waste(own, *required),
+                            Objective::MostLevelUps if own <= *required => waste(own, *required),
+                            Objective::MostLevelUps => level_up_weight + waste(own, *required),
+                        }
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    loop {
+        let assignment = min_cost_assignment(&costs)?;
+        let team: Vec<Id> = assignment.iter().map(|j| candidates[*j]).collect();
+        let mut valid = true;
+        for (role, (skill, required)) in roles.iter().enumerate() {
+            let mentored = level(team[role], *skill) < *required;
+            if mentored && !team.iter().any(|c| level(*c, *skill) >= *required) {
+                costs[role][assignment[role]] = FORBIDDEN;
+                valid = false;
+            }
+        }
+        if valid {
+            return Some(team);
+        }
+    }
+}
+
+/// Planned project whose contributors were changed by `restaff`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Restaffed {
+    pub project: String,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+/// Whether a contributor of `team` is not skilled enough for their role, even with mentoring.
+fn under_qualified(precomputed: &PreComputed, project: Id, team: &[Id]) -> bool {
+    let level = |contributor: Id, skill: Id| {
+        precomputed
+            .levels
+            .get(&(contributor, skill))
+            .cloned()
+            .unwrap_or(0)
+    };
+    precomputed.projects[project]
+        .skills
+        .iter()
+        .zip(team)
+        .any(|((skill, required), contributor)| {
+            let own = level(*contributor, *skill);
+            let team_levels = team.iter().map(|c| level(*c, *skill));
+            own < *required && !is_mentored(own, *required, team_levels)
+        })
+}
+
+/// Outcome of `restaff_project`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Staffing {
+    /// every contributor is skilled enough, possibly with mentoring
+    Qualified,
+    Restaffed(Restaffed),
+    /// no valid assignment among the team and the contributors free by its start
+    Unstaffable,
+}
+
+/// Checks a planned project against `state`, the contributors availability and levels right
+/// before it, and re-assigns its roles if a contributor is not skilled enough.
+///
+/// The project is first restaffed with its own contributors in another order, which keeps its
+/// start date, then with any contributor free by the time it starts, which may make it start
+/// earlier but never later.
+pub fn restaff_project(
+    state: &PreComputed,
+    planned_project: &PPlannedProject,
+    objective: Objective,
+) -> anyhow::Result<Staffing> {
+    let project = match state.projects_id.get(&planned_project.name) {
+        Some(project) => *project,
+        None => bail!("unknown project {}", planned_project.name),
+    };
+    let mut team: Vec<Id> = Vec::with_capacity(planned_project.contributor_names.len());
+    for name in &planned_project.contributor_names {
+        match state.contributors_id.get(name) {
+            Some(contributor) => team.push(*contributor),
+            None => bail!(
+                "unknown contributor {} for project {}",
+                name,
+                planned_project.name
+            ),
+        }
+    }
+    if !under_qualified(state, project, &team) {
+        return Ok(Staffing::Qualified);
+    }
+    let start = team
+        .iter()
+        .map(|c| state.contributors[*c].next_availability)
+        .max()
+        .unwrap_or(0);
+    let free: Vec<Id> = state
+        .contributors
+        .iter()
+        .filter(|c| c.next_availability <= start)
+        .map(|c| c.id)
+        .collect();
+    let assignment = assign_roles(state, project, &team, objective)
+        .or_else(|| assign_roles(state, project, &free, objective));
+    Ok(match assignment {
+        Some(assignment) => Staffing::Restaffed(Restaffed {
+            project: planned_project.name.clone(),
+            before: planned_project.contributor_names.clone(),
+            after: assignment
+                .iter()
+                .map(|c| state.contributors[*c].name.clone())
+                .collect(),
+        }),
+        None => Staffing::Unstaffable,
+    })
+}
+
+/// Simulates a single planned project from `state`, which then holds the contributors
+/// availability and levels right after it. Level checks are disabled.
+///
+/// Each call goes through `simulate` and allocates per-contributor bookkeeping, so playing
+/// every project of an output one by one costs O(projects × contributors).
+pub fn play_project(
+    state: &mut PreComputed,
+    planned_project: &PPlannedProject,
+) -> anyhow::Result<()> {
+    let single = POutput {
+        n_projects: 1,
+        projects: vec![planned_project.clone()],
+    };
+    simulate(state, &single, true, |_| {})
+}
+
+/// Re-assigns the roles of planned projects where a contributor is not skilled enough, in
+/// output file order, returns the new output and the projects that changed.
+///
+/// See `restaff_project`, projects that cannot be restaffed are left as they are. The output
+/// is first simulated as a whole to check names and role counts, then each project is
+/// restaffed with the contributors availability and levels right before it and played with
+/// `play_project`.
+pub fn restaff(
+    precomputed: &PreComputed,
+    output: &POutput,
+    objective: Objective,
+) -> anyhow::Result<(POutput, Vec<Restaffed>)> {
+    // unknown names and role counts, before changing anything
+    simulate(&mut precomputed.clone(), output, true, |_| {})?;
+    let mut output = output.clone();
+    let mut restaffed = vec![];
+    let mut state = precomputed.clone();
+    for planned_project in output.projects.iter_mut() {
+        if let Staffing::Restaffed(r) = restaff_project(&state, planned_project, objective)? {
+            planned_project.contributor_names = r.after.clone();
+            restaffed.push(r);
+        }
+        play_project(&mut state, planned_project)?;
+    }
+    Ok((output, restaffed))
+}
+
+impl fmt::Display for Restaffed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.project,
+            self.before.join(" "),
+            self.after.join(" ")
+        )
+    }
+}
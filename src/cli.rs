@@ -1,13 +1,23 @@
-use clap::{Arg, Command};
+use clap::{Arg, ArgGroup, Command};
+
+/// Rejects counts that must be at least 1, e.g. worker threads.
+fn at_least_one(value: &str) -> Result<(), String> {
+    match value.parse::<usize>() {
+        Ok(n) if n >= 1 => Ok(()),
+        _ => Err(String::from("expected a whole number of at least 1")),
+    }
+}
 
 pub fn get_command() -> Command<'static> {
     Command::new(crate_name!())
         .version(crate_version!())
         .author(crate_authors!("\n"))
         .about("Google Hashcode Score Calculator")
+        .subcommand_negates_reqs(true)
+        .args_conflicts_with_subcommands(true)
         .arg(
             Arg::new("input")
-                .help("input file paths")
+                .help("input file paths (- for stdin, .gz and .xz are decompressed)")
                 .multiple_values(true)
                 .required(true)
                 .takes_value(true),
@@ -15,7 +25,7 @@ pub fn get_command() -> Command<'static> {
         .arg(
             Arg::new("output")
                 .short('o')
-                .help("output file paths (one for each input provided file)")
+                .help("output file paths (one for each input provided file, - for stdin)")
                 .multiple_values(true)
                 .required(true)
                 .takes_value(true),
@@ -23,7 +33,7 @@ pub fn get_command() -> Command<'static> {
         .arg(
             Arg::new("disable-checks")
                 .long("--disable-checks")
-                .help("disable checks (contributors level)")
+                .help("disable contributors level checks (structural checks always apply)")
                 .required(false)
                 .takes_value(false),
         )
@@ -34,4 +44,601 @@ pub fn get_command() -> Command<'static> {
                 .required(false)
                 .takes_value(false),
         )
+        .arg(
+            Arg::new("json")
+                .long("--json")
+                .help("print a JSON report (scores and late projects) instead of scores")
+                .required(false)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::new("track")
+                .long("--track")
+                .help("record scores in .hashcode/history.jsonl")
+                .required(false)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::new("archive")
+                .long("--archive")
+                .help("copy output files beating the best score so far to best/<dataset>.out (implies --track)")
+                .required(false)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::new("watch")
+                .long("--watch")
+                .help("re-score output files whenever they change")
+                .required(false)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::new("watch-interval")
+                .long("--watch-interval")
+                .help("output files polling interval in milliseconds")
+                .required(false)
+                .takes_value(true)
+                .default_value("500")
+                .requires("watch"),
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("compare two output files for the same input file")
+                .arg(
+                    Arg::new("input")
+                        .help("input file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("output-a")
+                        .help("first output file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("output-b")
+                        .help("second output file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("disable-checks")
+                        .long("--disable-checks")
+                        .help("disable contributors level checks (structural checks always apply)")
+                        .required(false)
+                        .takes_value(false),
+                ),
+        )
+        .subcommand(
+            Command::new("explain")
+                .about("explain why a project scored less than its full value")
+                .arg(
+                    Arg::new("input")
+                        .help("input file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .help("output file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("project")
+                        .help("project name")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("disable-checks")
+                        .long("--disable-checks")
+                        .help("disable contributors level checks (structural checks always apply)")
+                        .required(false)
+                        .takes_value(false),
+                ),
+        )
+        .subcommand(
+            Command::new("state")
+                .about("contributors availability and levels at a given day or after a number of projects")
+                .arg(
+                    Arg::new("input")
+                        .help("input file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .help("output file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("day")
+                        .long("--day")
+                        .help("replay projects started on or before this day")
+                        .required(false)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("projects")
+                        .long("--projects")
+                        .help("replay the first N planned projects")
+                        .required(false)
+                        .takes_value(true),
+                )
+                .group(
+                    ArgGroup::new("until")
+                        .args(&["day", "projects"])
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("free")
+                        .long("--free")
+                        .help("only contributors free on that day")
+                        .required(false)
+                        .takes_value(false)
+                        .requires("day"),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("--json")
+                        .help("print a JSON array instead of one line per contributor")
+                        .required(false)
+                        .takes_value(false),
+                )
+                .arg(
+                    Arg::new("disable-checks")
+                        .long("--disable-checks")
+                        .help("disable contributors level checks (structural checks always apply)")
+                        .required(false)
+                        .takes_value(false),
+                ),
+        )
+        .subcommand(
+            Command::new("curve")
+                .about("cumulative score over time (CSV or SVG), to compare outputs for the same input")
+                .arg(
+                    Arg::new("input")
+                        .help("input file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("outputs")
+                        .help("output file paths (one curve each)")
+                        .multiple_values(true)
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("by")
+                        .long("--by")
+                        .help("x axis: day (projects score when they end) or project (output file order)")
+                        .required(false)
+                        .takes_value(true)
+                        .possible_values(["day", "project"])
+                        .default_value("day"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("--format")
+                        .help("csv or svg")
+                        .required(false)
+                        .takes_value(true)
+                        .possible_values(["csv", "svg"])
+                        .default_value("csv"),
+                )
+                .arg(
+                    Arg::new("file")
+                        .short('o')
+                        .help("file path to write (stdout if missing)")
+                        .required(false)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("disable-checks")
+                        .long("--disable-checks")
+                        .help("disable contributors level checks (structural checks always apply)")
+                        .required(false)
+                        .takes_value(false),
+                ),
+        )
+        .subcommand(
+            Command::new("marginal")
+                .about("score change if each planned project was removed, most harmful first")
+                .arg(
+                    Arg::new("input")
+                        .help("input file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .help("output file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("--json")
+                        .help("print a JSON array instead of one line per project")
+                        .required(false)
+                        .takes_value(false),
+                )
+                .arg(
+                    Arg::new("disable-checks")
+                        .long("--disable-checks")
+                        .help("disable contributors level checks (structural checks always apply)")
+                        .required(false)
+                        .takes_value(false),
+                ),
+        )
+        .subcommand(
+            Command::new("slack")
+                .about("days each project can be delayed and the chains of projects that make late projects late")
+                .arg(
+                    Arg::new("input")
+                        .help("input file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .help("output file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("all")
+                        .long("--all")
+                        .help("slack of every project instead of the critical chains of late projects")
+                        .required(false)
+                        .takes_value(false),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("--json")
+                        .help("print a JSON array")
+                        .required(false)
+                        .takes_value(false),
+                )
+                .arg(
+                    Arg::new("disable-checks")
+                        .long("--disable-checks")
+                        .help("disable contributors level checks (structural checks always apply)")
+                        .required(false)
+                        .takes_value(false),
+                ),
+        )
+        .subcommand(
+            Command::new("restaff")
+                .about("re-assign roles of projects with a contributor not skilled enough")
+                .arg(
+                    Arg::new("input")
+                        .help("input file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .help("output file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("file")
+                        .short('o')
+                        .help("restaffed output file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("objective")
+                        .long("--objective")
+                        .help("level-ups (most contributors leveling up) or waste (fewest levels above required)")
+                        .required(false)
+                        .takes_value(true)
+                        .possible_values(["level-ups", "waste"])
+                        .default_value("level-ups"),
+                ),
+        )
+        .subcommand(
+            Command::new("repair")
+                .about("fix an invalid output: remove unknown and duplicate names, restaff or drop projects with contributors not skilled enough")
+                .arg(
+                    Arg::new("input")
+                        .help("input file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .help("output file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("file")
+                        .short('o')
+                        .help("repaired output file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("objective")
+                        .long("--objective")
+                        .help("level-ups (most contributors leveling up) or waste (fewest levels above required)")
+                        .required(false)
+                        .takes_value(true)
+                        .possible_values(["level-ups", "waste"])
+                        .default_value("level-ups"),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("--json")
+                        .help("print the score and changes as JSON")
+                        .required(false)
+                        .takes_value(false),
+                ),
+        )
+        .subcommand(
+            Command::new("reorder")
+                .about("search a better order of the planned projects, keeping their contributors")
+                .arg(
+                    Arg::new("input")
+                        .help("input file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .help("output file path (must be valid)")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("file")
+                        .short('o')
+                        .help("reordered output file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("iterations")
+                        .long("--iterations")
+                        .help("number of moves tried, each one scores the whole output")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("1000"),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .long("--seed")
+                        .help("random seed, same seed and options give the same order")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("0"),
+                ),
+        )
+        .subcommand(
+            Command::new("mip-export")
+                .about("write the input as a time-indexed MIP model for offline solvers (small inputs only)")
+                .arg(
+                    Arg::new("input")
+                        .help("input file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("file")
+                        .short('o')
+                        .help("model file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("--format")
+                        .help("lp (CPLEX LP) or mps (free MPS)")
+                        .required(false)
+                        .takes_value(true)
+                        .possible_values(["lp", "mps"])
+                        .default_value("lp"),
+                )
+                .arg(
+                    Arg::new("horizon")
+                        .long("--horizon")
+                        .help("every project ends by this day, smaller models (default: sum of all projects duration)")
+                        .required(false)
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            Command::new("mip-import")
+                .about("turn a solver solution of a model written by mip-export into an output file")
+                .arg(
+                    Arg::new("input")
+                        .help("input file path")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("solution")
+                        .help("solution file path (variable name and value on each line)")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("file")
+                        .short('o')
+                        .help("output file path")
+                        .required(true)
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            Command::new("package")
+                .about(
+                    "package best outputs (best/<dataset>.out) and sources into a submission zip",
+                )
+                .arg(
+                    Arg::new("input")
+                        .help("input file paths (one for each dataset)")
+                        .multiple_values(true)
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("sources")
+                        .long("--sources")
+                        .help("source directory")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("zip")
+                        .short('o')
+                        .help("zip file path")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("submission.zip"),
+                ),
+        )
+        .subcommand(
+            Command::new("serve")
+                .about("serve scoring and a team leaderboard over HTTP on localhost")
+                .arg(
+                    Arg::new("input")
+                        .help("input file paths (one for each dataset)")
+                        .multiple_values(true)
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("port")
+                        .long("--port")
+                        .help("port to listen on (127.0.0.1 only, 0 picks a free port)")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("8000"),
+                )
+                .arg(
+                    Arg::new("workers")
+                        .long("--workers")
+                        .help("number of worker threads (at least 1)")
+                        .required(false)
+                        .takes_value(true)
+                        .validator(at_least_one)
+                        .default_value("4"),
+                )
+                .arg(
+                    Arg::new("leaderboard")
+                        .long("--leaderboard")
+                        .help("leaderboard directory (submissions are stored there)")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value(".hashcode/leaderboard"),
+                ),
+        )
+        .subcommand(
+            Command::new("generate")
+                .about("generate a random input file")
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .help("input file path to write (stdout if missing)")
+                        .required(false)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .long("--seed")
+                        .help("random seed, same seed and options give the same input")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("0"),
+                )
+                .arg(
+                    Arg::new("contributors")
+                        .long("--contributors")
+                        .help("number of contributors")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("100"),
+                )
+                .arg(
+                    Arg::new("projects")
+                        .long("--projects")
+                        .help("number of projects")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("1000"),
+                )
+                .arg(
+                    Arg::new("skills")
+                        .long("--skills")
+                        .help("number of distinct skills")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("50"),
+                )
+                .arg(
+                    Arg::new("max-skills")
+                        .long("--max-skills")
+                        .help("maximum number of skills per contributor")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("5"),
+                )
+                .arg(
+                    Arg::new("max-roles")
+                        .long("--max-roles")
+                        .help("maximum number of roles per project")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("5"),
+                )
+                .arg(
+                    Arg::new("max-level")
+                        .long("--max-level")
+                        .help("maximum skill level")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("10"),
+                )
+                .arg(
+                    Arg::new("levels")
+                        .long("--levels")
+                        .help("contributors level distribution: uniform, junior or senior")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("uniform"),
+                )
+                .arg(
+                    Arg::new("max-days")
+                        .long("--max-days")
+                        .help("maximum project duration in days")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("20"),
+                )
+                .arg(
+                    Arg::new("max-score")
+                        .long("--max-score")
+                        .help("maximum project score")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("500"),
+                )
+                .arg(
+                    Arg::new("deadline-tightness")
+                        .long("--deadline-tightness")
+                        .help("0: best before leaves room for the whole workload, 1: best before is the project duration")
+                        .required(false)
+                        .takes_value(true)
+                        .default_value("0.5"),
+                ),
+        )
 }
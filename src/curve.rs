@@ -0,0 +1,180 @@
+use crate::score::{ProjectScore, Score};
+use anyhow::bail;
+use std::fmt::Write;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Axis {
+    /// score earned by the end of each day
+    Day,
+    /// score earned by the first n planned projects, in output file order
+    Project,
+}
+
+impl FromStr for Axis {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(Axis::Day),
+            "project" => Ok(Axis::Project),
+            _ => bail!("unknown axis {:?}, expected day or project", s),
+        }
+    }
+}
+
+impl Axis {
+    fn name(&self) -> &'static str {
+        match self {
+            Axis::Day => "day",
+            Axis::Project => "project",
+        }
+    }
+}
+
+/// Cumulative score of one output, starting at (0, 0).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Curve {
+    pub name: String,
+    pub points: Vec<(usize, Score)>, // (day or number of projects, cumulative score)
+}
+
+/// Projects score when they end, `project_scores` must come from simulating a whole output.
+pub fn score_curve(name: &str, project_scores: &[ProjectScore], axis: Axis) -> Curve {
+    let mut points = vec![(0, 0)];
+    let mut total: Score = 0;
+    match axis {
+        Axis::Day => {
+            let mut by_end: Vec<&ProjectScore> = project_scores.iter().collect();
+            by_end.sort_by_key(|p| p.end);
+            for project_score in by_end {
+                total += project_score.score;
+                match points.last_mut() {
+                    Some(last) if last.0 == project_score.end => last.1 = total,
+                    _ => points.push((project_score.end, total)),
+                }
+            }
+        }
+        Axis::Project => {
+            for (index, project_score) in project_scores.iter().enumerate() {
+                total += project_score.score;
+                points.push((index + 1, total));
+            }
+        }
+    }
+    Curve {
+        name: name.to_string(),
+        points,
+    }
+}
+
+/// Quotes a CSV field if needed, doubling its quotes (RFC 4180).
+fn escape_csv(s: &str) -> String {
+    if s.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// One `output,<axis>,score` row per point.
+pub fn to_csv(curves: &[Curve], axis: Axis) -> String {
+    let mut csv = format!("output,{},score\n", axis.name());
+    for curve in curves {
+        let name = escape_csv(&curve.name);
+        for (x, score) in &curve.points {
+            let _ = writeln!(csv, "{},{},{}", name, x, score);
+        }
+    }
+    csv
+}
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 500.0;
+const MARGIN: f64 = 60.0;
+const COLORS: [&str; 6] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b",
+];
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Step plot of every curve on the same axes, with a legend.
+pub fn to_svg(curves: &[Curve], axis: Axis) -> String {
+    let max_x = curves
+        .iter()
+        .flat_map(|c| c.points.iter().map(|(x, _)| *x))
+        .max()
+        .unwrap_or(0)
+        .max(1) as f64;
+    let max_y = curves
+        .iter()
+        .flat_map(|c| c.points.iter().map(|(_, y)| *y))
+        .max()
+        .unwrap_or(0)
+        .max(1) as f64;
+    let scale_x = |x: usize| MARGIN + x as f64 / max_x * (WIDTH - 2.0 * MARGIN);
+    let scale_y = |y: Score| HEIGHT - MARGIN - y as f64 / max_y * (HEIGHT - 2.0 * MARGIN);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"12\">\n<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n",
+        w = WIDTH,
+        h = HEIGHT
+    );
+    // axes, with the maximum value at the end of each one
+    let _ = writeln!(
+        svg,
+        "<path d=\"M{l} {t} V{b} H{r}\" fill=\"none\" stroke=\"black\"/>",
+        l = MARGIN,
+        t = MARGIN,
+        b = HEIGHT - MARGIN,
+        r = WIDTH - MARGIN
+    );
+    let _ = writeln!(
+        svg,
+        "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{} ({})</text>",
+        WIDTH / 2.0,
+        HEIGHT - MARGIN / 3.0,
+        axis.name(),
+        max_x
+    );
+    let _ = writeln!(
+        svg,
+        "<text x=\"{}\" y=\"{}\">score ({})</text>",
+        MARGIN,
+        MARGIN - 10.0,
+        max_y
+    );
+    for (i, curve) in curves.iter().enumerate() {
+        let color = COLORS[i % COLORS.len()];
+        let mut d = String::new();
+        for (j, (x, y)) in curve.points.iter().enumerate() {
+            if j == 0 {
+                let _ = write!(d, "M{:.1} {:.1}", scale_x(*x), scale_y(*y));
+            } else {
+                // score is earned at the end of the day, keep the previous score until then
+                let _ = write!(d, " H{:.1} V{:.1}", scale_x(*x), scale_y(*y));
+            }
+        }
+        let _ = writeln!(
+            svg,
+            "<path d=\"{}\" fill=\"none\" stroke=\"{}\"/>",
+            d, color
+        );
+        let legend_y = MARGIN + 20.0 * i as f64;
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" fill=\"{}\">{}</text>",
+            MARGIN + 10.0,
+            legend_y + 15.0,
+            color,
+            escape_xml(&curve.name)
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
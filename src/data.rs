@@ -1,13 +1,16 @@
 pub type Name = String;
 pub type Id = usize;
 
-#[derive(Debug, PartialEq)]
+/// Upper bound on preallocation, counts come from untrusted headers.
+pub const MAX_PREALLOCATION: usize = 1 << 16;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct PContributorSkill {
     pub name: String,
     pub level: usize,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PContributor {
     pub name: String,
     pub n_skills: usize,
@@ -15,7 +18,7 @@ pub struct PContributor {
     pub id: Id, // initialized during parsing
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PProject {
     pub name: String,
     pub days_to_completion: usize,
@@ -26,7 +29,7 @@ pub struct PProject {
     pub id: Id, // initialized during parsing
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PInput {
     pub n_contributors: usize,
     pub n_projects: usize,
@@ -34,13 +37,13 @@ pub struct PInput {
     pub projects: Vec<PProject>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PPlannedProject {
     pub name: String,
     pub contributor_names: Vec<Name>, // contributors name
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct POutput {
     pub n_projects: usize,
     pub projects: Vec<PPlannedProject>,
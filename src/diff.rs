@@ -0,0 +1,198 @@
+use crate::data::POutput;
+use crate::score::{compute_project_scores_precomputed, PreComputed, ProjectScore, Score};
+use fxhash::FxHashMap;
+use num_format::{Locale, ToFormattedString};
+use std::fmt;
+
+#[derive(Debug)]
+pub struct ReorderedProject {
+    name: String,
+    rank_a: usize,
+    rank_b: usize,
+}
+
+#[derive(Debug)]
+pub struct ChangedRoles {
+    name: String,
+    contributors_a: Vec<String>,
+    contributors_b: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct ScoreDelta {
+    name: String,
+    score_a: Option<Score>,
+    score_b: Option<Score>,
+}
+
+impl ScoreDelta {
+    fn delta(&self) -> i64 {
+        self.score_b.unwrap_or(0) as i64 - self.score_a.unwrap_or(0) as i64
+    }
+}
+
+#[derive(Debug)]
+pub struct OutputDiff {
+    only_in_a: Vec<String>,
+    only_in_b: Vec<String>,
+    reordered: Vec<ReorderedProject>,
+    changed_roles: Vec<ChangedRoles>,
+    score_deltas: Vec<ScoreDelta>,
+    total_a: Score,
+    total_b: Score,
+}
+
+/// Scores both outputs against the same input and compares them project by project.
+///
+/// `precomputed` is left untouched, each output is simulated on its own copy.
+pub fn diff_outputs(
+    precomputed: &PreComputed,
+    output_a: &POutput,
+    output_b: &POutput,
+    disable_checks: bool,
+) -> anyhow::Result<OutputDiff> {
+    let scores_a =
+        compute_project_scores_precomputed(&mut precomputed.clone(), output_a, disable_checks)?;
+    let scores_b =
+        compute_project_scores_precomputed(&mut precomputed.clone(), output_b, disable_checks)?;
+
+    let by_name_a = index_by_name(output_a, &scores_a);
+    let by_name_b = index_by_name(output_b, &scores_b);
+
+    let only_in_a: Vec<String> = output_a
+        .projects
+        .iter()
+        .filter(|p| !by_name_b.contains_key(p.name.as_str()))
+        .map(|p| p.name.clone())
+        .collect();
+    let only_in_b: Vec<String> = output_b
+        .projects
+        .iter()
+        .filter(|p| !by_name_a.contains_key(p.name.as_str()))
+        .map(|p| p.name.clone())
+        .collect();
+
+    // rank of each project among the projects planned in both outputs
+    let common_ranks = |output: &POutput, other: &FxHashMap<&str, (usize, &ProjectScore)>| {
+        output
+            .projects
+            .iter()
+            .filter(|p| other.contains_key(p.name.as_str()))
+            .enumerate()
+            .map(|(rank, p)| (p.name.clone(), rank))
+            .collect::<FxHashMap<String, usize>>()
+    };
+    let common_ranks_a = common_ranks(output_a, &by_name_b);
+    let common_ranks_b = common_ranks(output_b, &by_name_a);
+
+    let mut reordered = vec![];
+    let mut changed_roles = vec![];
+    for project_a in &output_a.projects {
+        if let Some((index_b, _)) = by_name_b.get(project_a.name.as_str()) {
+            let rank_a = common_ranks_a[&project_a.name];
+            let rank_b = common_ranks_b[&project_a.name];
+            if rank_a != rank_b {
+                reordered.push(ReorderedProject {
+                    name: project_a.name.clone(),
+                    rank_a,
+                    rank_b,
+                });
+            }
+            let project_b = &output_b.projects[*index_b];
+            if project_a.contributor_names != project_b.contributor_names {
+                changed_roles.push(ChangedRoles {
+                    name: project_a.name.clone(),
+                    contributors_a: project_a.contributor_names.clone(),
+                    contributors_b: project_b.contributor_names.clone(),
+                });
+            }
+        }
+    }
+
+    let mut score_deltas: Vec<ScoreDelta> = output_a
+        .projects
+        .iter()
+        .map(|p| ScoreDelta {
+            name: p.name.clone(),
+            score_a: by_name_a.get(p.name.as_str()).map(|(_, s)| s.score),
+            score_b: by_name_b.get(p.name.as_str()).map(|(_, s)| s.score),
+        })
+        .chain(only_in_b.iter().map(|name| ScoreDelta {
+            name: name.clone(),
+            score_a: None,
+            score_b: by_name_b.get(name.as_str()).map(|(_, s)| s.score),
+        }))
+        .filter(|d| d.delta() != 0)
+        .collect();
+    // largest differences first
+    score_deltas.sort_by_key(|d| -d.delta().abs());
+
+    Ok(OutputDiff {
+        only_in_a,
+        only_in_b,
+        reordered,
+        changed_roles,
+        score_deltas,
+        total_a: scores_a.iter().map(|s| s.score).sum(),
+        total_b: scores_b.iter().map(|s| s.score).sum(),
+    })
+}
+
+fn index_by_name<'a>(
+    output: &'a POutput,
+    scores: &'a [ProjectScore],
+) -> FxHashMap<&'a str, (usize, &'a ProjectScore)> {
+    output
+        .projects
+        .iter()
+        .zip(scores)
+        .enumerate()
+        .map(|(index, (p, s))| (p.name.as_str(), (index, s)))
+        .collect()
+}
+
+fn format_score(score: Option<Score>) -> String {
+    match score {
+        Some(score) => score.to_formatted_string(&Locale::en),
+        None => String::from("-"),
+    }
+}
+
+impl fmt::Display for OutputDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "only in a: {}", self.only_in_a.join(" "))?;
+        writeln!(f, "only in b: {}", self.only_in_b.join(" "))?;
+        writeln!(f, "reordered ({}):", self.reordered.len())?;
+        for r in &self.reordered {
+            writeln!(f, "  {} {} -> {}", r.name, r.rank_a, r.rank_b)?;
+        }
+        writeln!(f, "changed roles ({}):", self.changed_roles.len())?;
+        for c in &self.changed_roles {
+            writeln!(
+                f,
+                "  {}: {} -> {}",
+                c.name,
+                c.contributors_a.join(" "),
+                c.contributors_b.join(" ")
+            )?;
+        }
+        writeln!(f, "score delta ({}):", self.score_deltas.len())?;
+        for d in &self.score_deltas {
+            writeln!(
+                f,
+                "  {}: {} -> {} ({:+})",
+                d.name,
+                format_score(d.score_a),
+                format_score(d.score_b),
+                d.delta()
+            )?;
+        }
+        write!(
+            f,
+            "total: {} -> {} ({:+})",
+            self.total_a.to_formatted_string(&Locale::en),
+            self.total_b.to_formatted_string(&Locale::en),
+            self.total_b as i64 - self.total_a as i64
+        )
+    }
+}
@@ -0,0 +1,91 @@
+use crate::data::POutput;
+use crate::score::{ProjectScore, Score, Time};
+use anyhow::bail;
+use serde::Serialize;
+use std::fmt;
+
+/// Contributor that pushed the start date of a late project.
+#[derive(Serialize, Debug)]
+pub struct Delay {
+    contributor: String,
+    previous_project: String,
+}
+
+/// Why a planned project scored less than its full value.
+#[derive(Serialize, Debug)]
+pub struct LateProject {
+    pub name: String,
+    start: Time,
+    end: Time,
+    best_before: Time,
+    days_late: usize,
+    lost: Score,
+    delayed_by: Option<Delay>,
+}
+
+fn late_project(output: &POutput, project_scores: &[ProjectScore], index: usize) -> LateProject {
+    let project_score = &project_scores[index];
+    let planned_project = &output.projects[index];
+    let delayed_by = project_score.blocker.as_ref().map(|blocker| Delay {
+        contributor: planned_project.contributor_names[blocker.role].clone(),
+        previous_project: output.projects[blocker.previous_planned_index].name.clone(),
+    });
+    LateProject {
+        name: planned_project.name.clone(),
+        start: project_score.start,
+        end: project_score.end,
+        best_before: project_score.best_before,
+        days_late: project_score.days_late(),
+        lost: project_score.lost(),
+        delayed_by,
+    }
+}
+
+/// Explains every late project, in output file order.
+///
+/// `project_scores` must come from simulating `output`.
+pub fn late_projects(output: &POutput, project_scores: &[ProjectScore]) -> Vec<LateProject> {
+    project_scores
+        .iter()
+        .enumerate()
+        .filter(|(_, project_score)| project_score.days_late() > 0)
+        .map(|(index, _)| late_project(output, project_scores, index))
+        .collect()
+}
+
+/// Explains a single project, `None` if it was completed in time.
+pub fn explain_project(
+    output: &POutput,
+    project_scores: &[ProjectScore],
+    project_name: &str,
+) -> anyhow::Result<Option<LateProject>> {
+    match output.projects.iter().position(|p| p.name == project_name) {
+        Some(index) if project_scores[index].days_late() > 0 => {
+            Ok(Some(late_project(output, project_scores, index)))
+        }
+        Some(_) => Ok(None),
+        None => bail!("project {} is not planned", project_name),
+    }
+}
+
+impl fmt::Display for LateProject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: ends on day {} ({} days late, best before {}), {} points lost",
+            self.name, self.end, self.days_late, self.best_before, self.lost
+        )?;
+        match &self.delayed_by {
+            Some(delay) => write!(
+                f,
+                "starts on day {} because {} is busy on {} until then",
+                self.start, delay.contributor, delay.previous_project
+            ),
+            None => write!(
+                f,
+                "starts on day {}, it cannot be completed in time",
+                self.start
+            ),
+        }
+    }
+}
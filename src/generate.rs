@@ -0,0 +1,184 @@
+use crate::data::{PContributor, PContributorSkill, PInput, PProject};
+use anyhow::{bail, Context};
+use rand::seq::index::sample;
+use rand::Rng;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LevelDistribution {
+    /// every level between 1 and max level is equally likely
+    Uniform,
+    /// each level is half as likely as the previous one, most contributors are juniors
+    Junior,
+    /// each level is half as likely as the next one, most contributors are seniors
+    Senior,
+}
+
+impl FromStr for LevelDistribution {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "uniform" => Ok(LevelDistribution::Uniform),
+            "junior" => Ok(LevelDistribution::Junior),
+            "senior" => Ok(LevelDistribution::Senior),
+            _ => bail!(
+                "unknown level distribution {:?}, expected uniform, junior or senior",
+                s
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneratorConfig {
+    pub seed: u64,
+    pub contributors: usize,
+    pub projects: usize,
+    pub skills: usize,
+    pub max_skills_per_contributor: usize,
+    pub max_roles: usize,
+    pub max_level: usize,
+    pub levels: LevelDistribution,
+    pub max_days: usize,
+    pub max_score: usize,
+    /// 0: best before leaves room for the whole workload, 1: best before is the project duration
+    pub deadline_tightness: f64,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        GeneratorConfig {
+            seed: 0,
+            contributors: 100,
+            projects: 1000,
+            skills: 50,
+            max_skills_per_contributor: 5,
+            max_roles: 5,
+            max_level: 10,
+            levels: LevelDistribution::Uniform,
+            max_days: 20,
+            max_score: 500,
+            deadline_tightness: 0.5,
+        }
+    }
+}
+
+impl GeneratorConfig {
+    fn check(&self) -> anyhow::Result<()> {
+        if self.contributors == 0 || self.skills == 0 {
+            bail!("at least one contributor and one skill are required");
+        }
+        if self.max_skills_per_contributor == 0 || self.max_roles == 0 {
+            bail!("max skills per contributor and max roles must be at least 1");
+        }
+        if self.max_level == 0 || self.max_days == 0 || self.max_score == 0 {
+            bail!("max level, max days and max score must be at least 1");
+        }
+        if !(0.0..=1.0).contains(&self.deadline_tightness) {
+            bail!(
+                "deadline tightness must be between 0 and 1, got {}",
+                self.deadline_tightness
+            );
+        }
+        Ok(())
+    }
+}
+
+fn random_level(rng: &mut ChaCha8Rng, max_level: usize, levels: LevelDistribution) -> usize {
+    match levels {
+        LevelDistribution::Uniform => rng.gen_range(1..=max_level),
+        LevelDistribution::Junior | LevelDistribution::Senior => {
+            // geometric with p = 1/2, capped at max level
+            let mut level = 1;
+            while level < max_level && rng.gen_bool(0.5) {
+                level += 1;
+            }
+            if levels == LevelDistribution::Senior {
+                max_level + 1 - level
+            } else {
+                level
+            }
+        }
+    }
+}
+
+/// Generates a random input, the same config (seed included) always yields the same input.
+///
+/// Each project draws distinct contributors, one per role, and each role requires one of the
+/// skills of its contributor at a level at most theirs: every project can be staffed on its own.
+pub fn generate(config: &GeneratorConfig) -> anyhow::Result<PInput> {
+    config.check()?;
+    let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+    let skill_name = |skill: usize| format!("s{}", skill);
+
+    let mut contributors = Vec::with_capacity(config.contributors);
+    for id in 0..config.contributors {
+        let n_skills = rng.gen_range(1..=config.max_skills_per_contributor.min(config.skills));
+        let skills: Vec<PContributorSkill> = sample(&mut rng, config.skills, n_skills)
+            .into_iter()
+            .map(|skill| PContributorSkill {
+                name: skill_name(skill),
+                level: random_level(&mut rng, config.max_level, config.levels),
+            })
+            .collect();
+        contributors.push(PContributor {
+            name: format!("c{}", id),
+            n_skills: skills.len(),
+            skills,
+            id,
+        });
+    }
+
+    let mut projects = Vec::with_capacity(config.projects);
+    for id in 0..config.projects {
+        let n_roles = rng.gen_range(1..=config.max_roles.min(config.contributors));
+        let skills: Vec<PContributorSkill> = sample(&mut rng, config.contributors, n_roles)
+            .into_iter()
+            .map(|contributor| {
+                let skills = &contributors[contributor].skills;
+                let skill = &skills[rng.gen_range(0..skills.len())];
+                PContributorSkill {
+                    name: skill.name.clone(),
+                    level: rng.gen_range(1..=skill.level),
+                }
+            })
+            .collect();
+        projects.push(PProject {
+            name: format!("p{}", id),
+            days_to_completion: rng.gen_range(1..=config.max_days),
+            score: rng.gen_range(1..=config.max_score),
+            best_before: 0, // set below, depends on the whole workload
+            n_roles: skills.len(),
+            skills,
+            id,
+        });
+    }
+
+    // days needed to complete every project if contributors were never idle
+    let workload = projects
+        .iter()
+        .try_fold(0usize, |sum, p| {
+            p.days_to_completion
+                .checked_mul(p.n_roles)
+                .and_then(|days| sum.checked_add(days))
+        })
+        .context("total workload overflows, reduce projects, max roles or max days")?
+        / config.contributors;
+    let max_slack = ((1.0 - config.deadline_tightness) * workload as f64) as usize;
+    for project in projects.iter_mut() {
+        project.best_before = project
+            .days_to_completion
+            .checked_add(rng.gen_range(0..=max_slack))
+            .with_context(|| format!("best before of {} overflows", project.name))?;
+    }
+
+    Ok(PInput {
+        n_contributors: contributors.len(),
+        n_projects: projects.len(),
+        contributors,
+        projects,
+    })
+}
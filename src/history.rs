@@ -0,0 +1,167 @@
+use crate::score::Score;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs::{create_dir_all, write, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const HISTORY_PATH: &str = ".hashcode/history.jsonl";
+pub const BEST_DIR: &str = "best";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Entry {
+    pub timestamp: u64, // seconds since unix epoch
+    pub dataset: String,
+    pub output: String,
+    pub score: Score,
+    #[serde(default)]
+    pub archived: bool, // copied to best/<dataset>.out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>, // submitter, leaderboard only
+}
+
+impl Entry {
+    pub fn new(dataset: &str, output: &str, score: Score) -> anyhow::Result<Self> {
+        Ok(Entry {
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            dataset: dataset.to_string(),
+            output: output.to_string(),
+            score,
+            archived: false,
+            user: None,
+        })
+    }
+}
+
+/// Append-only score history, one JSON entry per line, and the best outputs directory.
+pub struct History {
+    path: PathBuf,
+    best_dir: PathBuf,
+    entries: Vec<Entry>,
+}
+
+/// Dataset name from an input file path, e.g. `res/a_an_example.in.txt` -> `a_an_example`.
+pub fn dataset_name(input_file_path: &str) -> String {
+    let file_name = Path::new(input_file_path)
+        .file_name()
+        .map(|f| f.to_string_lossy().into_owned())
+        .unwrap_or_else(|| input_file_path.to_string());
+    match file_name.split_once('.') {
+        Some((dataset, _)) if !dataset.is_empty() => dataset.to_string(),
+        _ => file_name,
+    }
+}
+
+pub fn best_output_path(best_dir: &Path, dataset: &str) -> PathBuf {
+    best_dir.join(format!("{}.out", dataset))
+}
+
+impl History {
+    /// Loads the history, a missing file is an empty history. Best outputs go to `BEST_DIR`.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut entries = vec![];
+        if path.exists() {
+            let reader = BufReader::new(File::open(path)?);
+            for line in reader.lines() {
+                let line = line?;
+                if !line.trim().is_empty() {
+                    entries.push(serde_json::from_str(&line)?);
+                }
+            }
+        }
+        Ok(History {
+            path: path.to_path_buf(),
+            best_dir: PathBuf::from(BEST_DIR),
+            entries,
+        })
+    }
+
+    pub fn with_best_dir(self, best_dir: &Path) -> Self {
+        History {
+            best_dir: best_dir.to_path_buf(),
+            ..self
+        }
+    }
+
+    fn best_archived(&self, dataset: &str) -> Option<Score> {
+        self.entries
+            .iter()
+            .filter(|e| e.dataset == dataset && e.archived)
+            .map(|e| e.score)
+            .max()
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn record(&mut self, entry: Entry) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        self.entries.push(entry);
+        Ok(())
+    }
+}
+
+/// Records `score` and, if `archive` is set, writes `content` to `<best_dir>/<dataset>.out`
+/// when it beats the previously archived best (or when no best output is stored yet).
+///
+/// `content` is the text that was scored, not read again from `output_file_path`: the file may
+/// have been rewritten since, or be compressed.
+///
+/// Returns the archive path if the output was archived.
+pub fn track(
+    history: &mut History,
+    dataset: &str,
+    output_file_path: &str,
+    content: &str,
+    score: Score,
+    archive: bool,
+) -> anyhow::Result<Option<PathBuf>> {
+    let best_path = best_output_path(&history.best_dir, dataset);
+    let previous_best = history.best_archived(dataset);
+    let improved = previous_best.is_none_or(|best| score > best) || !best_path.exists();
+    let archived = archive && improved;
+    if archived {
+        create_dir_all(&history.best_dir)?;
+        write(&best_path, content)?;
+        info!(
+            "{} score {} (previous best {:?}) archived to {}",
+            output_file_path,
+            score,
+            previous_best,
+            best_path.display()
+        );
+    }
+    history.record(Entry {
+        archived,
+        ..Entry::new(dataset, output_file_path, score)?
+    })?;
+    Ok(if archived { Some(best_path) } else { None })
+}
+
+/// `track` when there is a history, errors are only logged: the score is still valid.
+pub fn try_track(
+    history: Option<&mut History>,
+    dataset: &str,
+    output_file_path: &str,
+    content: &str,
+    score: Score,
+    archive: bool,
+) -> Option<PathBuf> {
+    let history = history?;
+    match track(history, dataset, output_file_path, content, score, archive) {
+        Ok(archived) => archived,
+        Err(e) => {
+            warn!("could not record the score of {}: {}", output_file_path, e);
+            None
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::history::{Entry, History};
+use crate::score::Score;
+use anyhow::bail;
+use num_format::{Locale, ToFormattedString};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs::{create_dir_all, write};
+use std::path::{Path, PathBuf};
+
+const SUBMISSIONS_FILE: &str = "submissions.jsonl";
+
+/// Submissions of every user, kept on disk under `dir`.
+///
+/// Each valid submission is stored as `<dir>/<user>/<dataset>/<timestamp>-<n>.out`
+/// and recorded in `<dir>/submissions.jsonl`.
+pub struct Leaderboard {
+    dir: PathBuf,
+    history: History,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Standing {
+    pub user: String,
+    pub best: BTreeMap<String, Score>, // dataset -> best score
+    pub total_score: Score,
+}
+
+fn check_user_name(user: &str) -> anyhow::Result<()> {
+    let valid = !user.is_empty()
+        && user.len() <= 64
+        && user
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        bail!(
+            "invalid user name {:?}, expected up to 64 letters, digits, '-' or '_'",
+            user
+        );
+    }
+    Ok(())
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+impl Leaderboard {
+    pub fn load(dir: &Path) -> anyhow::Result<Self> {
+        let history = History::load(&dir.join(SUBMISSIONS_FILE))?;
+        Ok(Leaderboard {
+            dir: dir.to_path_buf(),
+            history,
+        })
+    }
+
+    pub fn submit(
+        &mut self,
+        user: &str,
+        dataset: &str,
+        output_content: &str,
+        score: Score,
+    ) -> anyhow::Result<()> {
+        check_user_name(user)?;
+        let entry = Entry::new(dataset, "", score)?;
+        let submission_dir = self.dir.join(user).join(dataset);
+        create_dir_all(&submission_dir)?;
+        let n = self.history.entries().len();
+        let output_path = submission_dir.join(format!("{}-{}.out", entry.timestamp, n));
+        write(&output_path, output_content)?;
+        self.history.record(Entry {
+            output: output_path.to_string_lossy().into_owned(),
+            user: Some(user.to_string()),
+            ..entry
+        })
+    }
+
+    /// Best score per dataset for each user, best total first, ties by user name.
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut best: BTreeMap<&str, BTreeMap<String, Score>> = BTreeMap::new();
+        for entry in self.history.entries() {
+            if let Some(user) = &entry.user {
+                let user_best = best.entry(user).or_default();
+                let score = user_best.entry(entry.dataset.clone()).or_insert(0);
+                *score = (*score).max(entry.score);
+            }
+        }
+        let mut standings: Vec<Standing> = best
+            .into_iter()
+            .map(|(user, best)| Standing {
+                user: user.to_string(),
+                total_score: best.values().sum(),
+                best,
+            })
+            .collect();
+        standings.sort_by_key(|s| std::cmp::Reverse(s.total_score));
+        standings
+    }
+
+    /// Every submission, oldest first, optionally for a single user.
+    pub fn history(&self, user: Option<&str>) -> Vec<&Entry> {
+        self.history
+            .entries()
+            .iter()
+            .filter(|e| e.user.is_some() && (user.is_none() || e.user.as_deref() == user))
+            .collect()
+    }
+
+    pub fn to_html(&self, datasets: &[String]) -> String {
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Leaderboard</title></head>\n<body>\n<h1>Leaderboard</h1>\n<table border=\"1\">\n<tr><th>#</th><th>user</th>",
+        );
+        for dataset in datasets {
+            let _ = write!(html, "<th>{}</th>", escape_html(dataset));
+        }
+        html.push_str("<th>total</th></tr>\n");
+        for (rank, standing) in self.standings().iter().enumerate() {
+            let _ = write!(
+                html,
+                "<tr><td>{}</td><td>{}</td>",
+                rank + 1,
+                escape_html(&standing.user)
+            );
+            for dataset in datasets {
+                let score = standing
+                    .best
+                    .get(dataset)
+                    .map(|s| s.to_formatted_string(&Locale::en))
+                    .unwrap_or_else(|| String::from("-"));
+                let _ = write!(html, "<td>{}</td>", score);
+            }
+            let _ = writeln!(
+                html,
+                "<td>{}</td></tr>",
+                standing.total_score.to_formatted_string(&Locale::en)
+            );
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        html
+    }
+}
@@ -0,0 +1,75 @@
+use crate::data::Id;
+use fxhash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+pub type Level = usize;
+
+/// Previous level storage, kept as a reference for benchmarks.
+pub type HashLevelMap = FxHashMap<(Id, Id), Level>; // contributor id, skill id, level
+
+/// Contributors skill levels, indexed by (contributor id, skill id).
+///
+/// One small vec of (skill id, level) per contributor, indexed by contributor id.
+/// Contributors only have a handful of skills so a lookup is a short linear scan
+/// over contiguous memory instead of hashing, and learning a new skill is a push.
+/// Same API as the `FxHashMap<(Id, Id), Level>` it replaces.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct LevelMap {
+    levels: Vec<Vec<(Id, Level)>>, // contributor id -> (skill id, level)
+}
+
+impl LevelMap {
+    pub fn get(&self, (contributor, skill): &(Id, Id)) -> Option<&Level> {
+        self.levels
+            .get(*contributor)?
+            .iter()
+            .find(|(s, _)| s == skill)
+            .map(|(_, level)| level)
+    }
+
+    pub fn get_mut(&mut self, (contributor, skill): &(Id, Id)) -> Option<&mut Level> {
+        self.levels
+            .get_mut(*contributor)?
+            .iter_mut()
+            .find(|(s, _)| s == skill)
+            .map(|(_, level)| level)
+    }
+
+    /// Sets the level, returns the previous one if any.
+    pub fn insert(&mut self, (contributor, skill): (Id, Id), level: Level) -> Option<Level> {
+        if let Some(previous) = self.get_mut(&(contributor, skill)) {
+            return Some(std::mem::replace(previous, level));
+        }
+        if contributor >= self.levels.len() {
+            self.levels.resize_with(contributor + 1, Vec::new);
+        }
+        self.levels[contributor].push((skill, level));
+        None
+    }
+
+    pub fn retain<F: FnMut(&(Id, Id), &mut Level) -> bool>(&mut self, mut f: F) {
+        for (contributor, skills) in self.levels.iter_mut().enumerate() {
+            skills.retain_mut(|(skill, level)| f(&(contributor, *skill), level));
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.levels.iter().map(|skills| skills.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// ((contributor id, skill id), level), by contributor id.
+    pub fn iter(&self) -> impl Iterator<Item = ((Id, Id), &Level)> {
+        self.levels
+            .iter()
+            .enumerate()
+            .flat_map(|(contributor, skills)| {
+                skills
+                    .iter()
+                    .map(move |(skill, level)| ((contributor, *skill), level))
+            })
+    }
+}
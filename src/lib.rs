@@ -0,0 +1,28 @@
+extern crate anyhow;
+extern crate fxhash;
+
+pub mod assign;
+pub mod curve;
+pub mod data;
+pub mod diff;
+pub mod explain;
+pub mod generate;
+pub mod history;
+pub mod leaderboard;
+pub mod levels;
+pub mod load;
+pub mod marginal;
+pub mod mip;
+pub mod package;
+pub mod parser;
+pub mod reorder;
+pub mod repair;
+pub mod report;
+pub mod score;
+pub mod server;
+pub mod slack;
+pub mod staffing;
+pub mod state;
+pub mod stream;
+pub mod watch;
+pub mod writer;
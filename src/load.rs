@@ -0,0 +1,80 @@
+use crate::data::POutput;
+use crate::parser::parse_output;
+use crate::score::{decode_precomputed, encode_precomputed, PreComputed};
+use crate::stream::precompute_from_reader;
+use flate2::read::GzDecoder;
+use log::{debug, info, warn};
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{stdin, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use xz2::read::XzDecoder;
+
+/// Path meaning "read from stdin".
+pub const STDIN: &str = "-";
+
+/// Opens a file, `-` is stdin, `.gz` and `.xz` files are decompressed.
+pub fn open_reader(file_path: &str) -> anyhow::Result<Box<dyn BufRead>> {
+    if file_path == STDIN {
+        return Ok(Box::new(BufReader::new(stdin())));
+    }
+    let path = Path::new(file_path);
+    let file = File::open(path)?;
+    Ok(match path.extension().and_then(OsStr::to_str) {
+        Some("gz") => Box::new(BufReader::new(GzDecoder::new(file))),
+        Some("xz") => Box::new(BufReader::new(XzDecoder::new(file))),
+        _ => Box::new(BufReader::new(file)),
+    })
+}
+
+/// Reads a whole file, see `open_reader`.
+pub fn read_content(file_path: &str) -> anyhow::Result<String> {
+    let mut content = String::new();
+    open_reader(file_path)?.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+pub fn load_precomputed(
+    input_file_path: &str,
+    generate_cache_files: bool,
+) -> anyhow::Result<PreComputed> {
+    let path = PathBuf::from_str(input_file_path)?;
+    let precomputed = if path.extension() == Some(OsStr::new("bin")) {
+        decode_precomputed(&path)?
+    } else {
+        info!("parsing {}", input_file_path);
+        let precomputed = precompute_from_reader(open_reader(input_file_path)?)?;
+        if generate_cache_files {
+            if input_file_path == STDIN {
+                warn!("no cache file generated for input read from stdin");
+            } else {
+                let dump_path = cache_path(&path);
+                encode_precomputed(&precomputed, &dump_path)?;
+            }
+        }
+        precomputed
+    };
+    Ok(precomputed)
+}
+
+/// `res/a.in.txt` -> `res/a.in.bin`, `res/a.in.txt.gz` -> `res/a.in.bin`.
+fn cache_path(input_path: &Path) -> PathBuf {
+    match input_path.extension().and_then(OsStr::to_str) {
+        Some("gz") | Some("xz") => input_path.with_extension("").with_extension("bin"),
+        _ => input_path.with_extension("bin"),
+    }
+}
+
+pub fn load_output(output_file_path: &str) -> anyhow::Result<POutput> {
+    Ok(load_output_and_content(output_file_path)?.0)
+}
+
+/// Parsed output and the text it was parsed from, decompressed.
+pub fn load_output_and_content(output_file_path: &str) -> anyhow::Result<(POutput, String)> {
+    let output_content = read_content(output_file_path)?;
+    info!("parsing {}", output_file_path);
+    let output_data = parse_output(&output_content)?;
+    debug!("{:?}", output_data);
+    Ok((output_data, output_content))
+}
@@ -1,31 +1,45 @@
 #[macro_use]
 extern crate clap;
-extern crate anyhow;
-extern crate fxhash;
 
-use crate::parser::{parse_input, parse_output};
-use crate::score::{
-    compute_score_precomputed, decode_precomputed, encode_precomputed, precompute_from_input, Score,
+use anyhow::{bail, Context};
+use clap::ArgMatches;
+use google_hashcode_score_2022::assign::{restaff, Objective};
+use google_hashcode_score_2022::curve::{score_curve, to_csv, to_svg, Axis};
+use google_hashcode_score_2022::diff::diff_outputs;
+use google_hashcode_score_2022::explain::explain_project;
+use google_hashcode_score_2022::generate::{generate, GeneratorConfig};
+use google_hashcode_score_2022::history::{
+    best_output_path, dataset_name, try_track, History, BEST_DIR, HISTORY_PATH,
 };
-use anyhow::bail;
-use log::{debug, info, warn};
+use google_hashcode_score_2022::leaderboard::Leaderboard;
+use google_hashcode_score_2022::load::{
+    load_output, load_output_and_content, load_precomputed, read_content, STDIN,
+};
+use google_hashcode_score_2022::marginal::leave_one_out;
+use google_hashcode_score_2022::mip::{build_model, import_solution, parse_solution};
+use google_hashcode_score_2022::package::{package, ValidOutput};
+use google_hashcode_score_2022::parser::parse_input;
+use google_hashcode_score_2022::reorder::{reorder, ReorderConfig};
+use google_hashcode_score_2022::repair::repair;
+use google_hashcode_score_2022::report::{FileReport, Report};
+use google_hashcode_score_2022::score::{
+    compute_project_scores_precomputed, compute_score_precomputed, precompute_from_input,
+};
+use google_hashcode_score_2022::server::{serve, Dataset, Datasets};
+use google_hashcode_score_2022::slack::{critical_chains, project_slacks};
+use google_hashcode_score_2022::state::{contributors_state, replay_until, Until};
+use google_hashcode_score_2022::watch::{watch, Watched};
+use google_hashcode_score_2022::writer::{write_input, write_output};
+use log::{info, warn};
 use num_format::{Locale, ToFormattedString};
-use std::ffi::OsString;
-use std::fs::read_to_string;
-use std::path::PathBuf;
-use std::str::FromStr;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
 
 mod cli;
-mod data;
-mod parser;
-mod score;
 
-fn main() -> anyhow::Result<()> {
-    // cf https://crates.io/crates/env_logger
-    env_logger::init();
-
-    // parse command line arguments
-    let matches = cli::get_command().get_matches();
+fn score(matches: &ArgMatches) -> anyhow::Result<()> {
     let input_files = matches.values_of("input").expect("input files compulsory");
     let output_files = matches
         .values_of("output")
@@ -46,40 +60,511 @@ fn main() -> anyhow::Result<()> {
     if generate_cache_files {
         warn!("cache file will be generated, expect slight performance degradation for this run.")
     }
-    let mut total_score: Score = 0;
+    let archive = matches.is_present("archive");
+    let track = archive || matches.is_present("track");
+    let stdin_output = output_files.clone().any(|o| o == STDIN);
+    if stdin_output && (archive || matches.is_present("watch")) {
+        bail!("--archive and --watch require output files, not stdin");
+    }
+    // the score does not depend on the history, scoring goes on without it
+    let mut history = if track {
+        match History::load(Path::new(HISTORY_PATH)) {
+            Ok(history) => Some(history),
+            Err(e) => {
+                warn!(
+                    "could not load {}, scores are not recorded: {}",
+                    HISTORY_PATH, e
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+    if matches.is_present("watch") {
+        let interval: u64 = matches.value_of_t("watch-interval")?;
+        let mut watched = Vec::with_capacity(input_files.len());
+        for (input_file_path, output_file_path) in input_files.zip(output_files) {
+            let precomputed = load_precomputed(input_file_path, generate_cache_files)?;
+            watched.push(Watched::new(input_file_path, output_file_path, precomputed));
+        }
+        return watch(
+            watched,
+            disable_checks,
+            Duration::from_millis(interval),
+            history.as_mut(),
+            archive,
+        );
+    }
+    let json = matches.is_present("json");
+    let mut report = Report::default();
     let input_output_files = input_files.zip(output_files);
     for (input_file_path, output_file_path) in input_output_files {
-        let path = PathBuf::from_str(output_file_path)?;
-        let output_content = read_to_string(path)?;
-        info!("parsing {}", output_file_path);
         // parsing output first since it is most likely to fail
-        let output_data = parse_output(&output_content)?;
-        debug!("{:?}", output_data);
-
-        let path = PathBuf::from_str(input_file_path)?;
-        let mut precomputed = if path.extension() == Some(&OsString::from_str("bin").unwrap()) {
-            decode_precomputed(&path)?
-        } else {
-            let input_content = read_to_string(&path)?;
-            info!("parsing {}", input_file_path);
-            let input_data = parse_input(&input_content)?;
-            debug!("{:?}", input_data);
-            let precomputed = precompute_from_input(&input_data);
-            if generate_cache_files {
-                let dump_path = PathBuf::from(&path).with_extension("bin");
-                encode_precomputed(&precomputed, &dump_path)?;
-            }
-            precomputed
-        };
+        let (output_data, output_content) = load_output_and_content(output_file_path)?;
+        let mut precomputed = load_precomputed(input_file_path, generate_cache_files)?;
 
-        let score = compute_score_precomputed(&mut precomputed, &output_data, disable_checks)?;
-        total_score += score;
-        let formatted_score = score.to_formatted_string(&Locale::en);
-        println!("{} score: {}", output_file_path, formatted_score);
+        let project_scores =
+            compute_project_scores_precomputed(&mut precomputed, &output_data, disable_checks)?;
+        let file_report = FileReport::new(
+            input_file_path,
+            output_file_path,
+            &output_data,
+            &project_scores,
+        );
+        let archived = try_track(
+            history.as_mut(),
+            &dataset_name(input_file_path),
+            output_file_path,
+            &output_content,
+            file_report.score,
+            archive,
+        );
+        if !json {
+            let formatted_score = file_report.score.to_formatted_string(&Locale::en);
+            println!("{} score: {}", output_file_path, formatted_score);
+            if let Some(best_path) = archived {
+                println!("new best archived to {}", best_path.display());
+            }
+        }
+        report.push(file_report);
     }
-    if many {
-        let formatted_score = total_score.to_formatted_string(&Locale::en);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if many {
+        let formatted_score = report.total_score.to_formatted_string(&Locale::en);
         println!("total score: {}", formatted_score);
     }
     Ok(())
 }
+
+fn diff(matches: &ArgMatches) -> anyhow::Result<()> {
+    let input_file_path = matches.value_of("input").expect("input file compulsory");
+    let output_a_file_path = matches
+        .value_of("output-a")
+        .expect("output file compulsory");
+    let output_b_file_path = matches
+        .value_of("output-b")
+        .expect("output file compulsory");
+    let disable_checks = matches.is_present("disable-checks");
+    if disable_checks {
+        warn!("checks are disabled, score may be overestimated if output files are incorrect.")
+    }
+    let output_a = load_output(output_a_file_path)?;
+    let output_b = load_output(output_b_file_path)?;
+    let precomputed = load_precomputed(input_file_path, false)?;
+    let output_diff = diff_outputs(&precomputed, &output_a, &output_b, disable_checks)?;
+    println!("a: {}", output_a_file_path);
+    println!("b: {}", output_b_file_path);
+    println!("{}", output_diff);
+    Ok(())
+}
+
+fn explain(matches: &ArgMatches) -> anyhow::Result<()> {
+    let input_file_path = matches.value_of("input").expect("input file compulsory");
+    let output_file_path = matches.value_of("output").expect("output file compulsory");
+    let project_name = matches.value_of("project").expect("project compulsory");
+    let disable_checks = matches.is_present("disable-checks");
+    if disable_checks {
+        warn!("checks are disabled, score may be overestimated if output files are incorrect.")
+    }
+    let output_data = load_output(output_file_path)?;
+    let mut precomputed = load_precomputed(input_file_path, false)?;
+    let project_scores =
+        compute_project_scores_precomputed(&mut precomputed, &output_data, disable_checks)?;
+    match explain_project(&output_data, &project_scores, project_name)? {
+        Some(late_project) => println!("{}", late_project),
+        None => println!("{}: completed in time, full score", project_name),
+    }
+    Ok(())
+}
+
+fn state(matches: &ArgMatches) -> anyhow::Result<()> {
+    let input_file_path = matches.value_of("input").expect("input file compulsory");
+    let output_file_path = matches.value_of("output").expect("output file compulsory");
+    let disable_checks = matches.is_present("disable-checks");
+    let until = if matches.is_present("day") {
+        Until::Day(matches.value_of_t("day")?)
+    } else {
+        Until::Projects(matches.value_of_t("projects")?)
+    };
+    let output_data = load_output(output_file_path)?;
+    let precomputed = load_precomputed(input_file_path, false)?;
+    let state = replay_until(&precomputed, &output_data, disable_checks, until)?;
+    let mut contributors = contributors_state(&state);
+    if let (Until::Day(day), true) = (until, matches.is_present("free")) {
+        contributors.retain(|c| c.next_availability <= day);
+    }
+    if matches.is_present("json") {
+        println!("{}", serde_json::to_string_pretty(&contributors)?);
+    } else {
+        for contributor in &contributors {
+            println!("{}", contributor);
+        }
+    }
+    Ok(())
+}
+
+fn curve(matches: &ArgMatches) -> anyhow::Result<()> {
+    let input_file_path = matches.value_of("input").expect("input file compulsory");
+    let output_files = matches
+        .values_of("outputs")
+        .expect("output files compulsory");
+    let disable_checks = matches.is_present("disable-checks");
+    let axis: Axis = matches.value_of_t("by")?;
+    let precomputed = load_precomputed(input_file_path, false)?;
+    let mut curves = vec![];
+    for output_file_path in output_files {
+        let output_data = load_output(output_file_path)?;
+        let project_scores = compute_project_scores_precomputed(
+            &mut precomputed.clone(),
+            &output_data,
+            disable_checks,
+        )?;
+        curves.push(score_curve(output_file_path, &project_scores, axis));
+    }
+    let content = match matches.value_of("format") {
+        Some("svg") => to_svg(&curves, axis),
+        _ => to_csv(&curves, axis),
+    };
+    match matches.value_of("file") {
+        Some(path) => {
+            std::fs::write(path, content).with_context(|| format!("cannot write {}", path))?
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+fn marginal(matches: &ArgMatches) -> anyhow::Result<()> {
+    let input_file_path = matches.value_of("input").expect("input file compulsory");
+    let output_file_path = matches.value_of("output").expect("output file compulsory");
+    let disable_checks = matches.is_present("disable-checks");
+    let output_data = load_output(output_file_path)?;
+    let precomputed = load_precomputed(input_file_path, false)?;
+    let mut marginals = leave_one_out(&precomputed, &output_data, disable_checks)?;
+    // most harmful first, projects that cannot be removed last
+    marginals.sort_by_key(|m| std::cmp::Reverse(m.delta.unwrap_or(i64::MIN)));
+    if matches.is_present("json") {
+        println!("{}", serde_json::to_string_pretty(&marginals)?);
+    } else {
+        for marginal in &marginals {
+            println!("{}", marginal);
+        }
+        let harmful = marginals
+            .iter()
+            .filter(|m| m.delta.is_some_and(|delta| delta > 0))
+            .count();
+        println!("{} projects cost more than they earn", harmful);
+    }
+    Ok(())
+}
+
+fn slack(matches: &ArgMatches) -> anyhow::Result<()> {
+    let input_file_path = matches.value_of("input").expect("input file compulsory");
+    let output_file_path = matches.value_of("output").expect("output file compulsory");
+    let disable_checks = matches.is_present("disable-checks");
+    let output_data = load_output(output_file_path)?;
+    let mut precomputed = load_precomputed(input_file_path, false)?;
+    let project_scores =
+        compute_project_scores_precomputed(&mut precomputed, &output_data, disable_checks)?;
+    let slacks = project_slacks(&output_data, &project_scores);
+    match (matches.is_present("all"), matches.is_present("json")) {
+        (true, true) => println!("{}", serde_json::to_string_pretty(&slacks)?),
+        (true, false) => {
+            for slack in &slacks {
+                println!("{}", slack);
+            }
+        }
+        (false, true) => println!(
+            "{}",
+            serde_json::to_string_pretty(&critical_chains(&slacks))?
+        ),
+        (false, false) => {
+            let chains = critical_chains(&slacks);
+            for chain in &chains {
+                println!("{}", chain);
+            }
+            println!("{} late projects", chains.len());
+        }
+    }
+    Ok(())
+}
+
+fn restaff_output(matches: &ArgMatches) -> anyhow::Result<()> {
+    let input_file_path = matches.value_of("input").expect("input file compulsory");
+    let output_file_path = matches.value_of("output").expect("output file compulsory");
+    let restaffed_file_path = matches.value_of("file").expect("file compulsory");
+    let objective: Objective = matches.value_of_t("objective")?;
+    let output_data = load_output(output_file_path)?;
+    let precomputed = load_precomputed(input_file_path, false)?;
+    let (restaffed_output, restaffed) = restaff(&precomputed, &output_data, objective)?;
+    let mut writer = BufWriter::new(
+        File::create(restaffed_file_path)
+            .with_context(|| format!("cannot create {}", restaffed_file_path))?,
+    );
+    write_output(&restaffed_output, &mut writer)?;
+    writer.flush()?;
+    for project in &restaffed {
+        println!("{}", project);
+    }
+    println!("{} projects restaffed", restaffed.len());
+    match compute_score_precomputed(&mut precomputed.clone(), &restaffed_output, false) {
+        Ok(score) => println!("{} score: {}", restaffed_file_path, score),
+        Err(e) => println!("{} is still invalid: {}", restaffed_file_path, e),
+    }
+    Ok(())
+}
+
+fn repair_output(matches: &ArgMatches) -> anyhow::Result<()> {
+    let input_file_path = matches.value_of("input").expect("input file compulsory");
+    let output_file_path = matches.value_of("output").expect("output file compulsory");
+    let repaired_file_path = matches.value_of("file").expect("file compulsory");
+    let objective: Objective = matches.value_of_t("objective")?;
+    let output_data = load_output(output_file_path)?;
+    let precomputed = load_precomputed(input_file_path, false)?;
+    let repaired = repair(&precomputed, &output_data, objective)?;
+    let mut writer = BufWriter::new(
+        File::create(repaired_file_path)
+            .with_context(|| format!("cannot create {}", repaired_file_path))?,
+    );
+    write_output(&repaired.output, &mut writer)?;
+    writer.flush()?;
+    if matches.is_present("json") {
+        println!("{}", serde_json::to_string_pretty(&repaired)?);
+    } else {
+        for change in &repaired.changes {
+            println!("{}", change);
+        }
+        println!(
+            "{} changes, {} score: {}",
+            repaired.changes.len(),
+            repaired_file_path,
+            repaired.score
+        );
+    }
+    Ok(())
+}
+
+fn reorder_output(matches: &ArgMatches) -> anyhow::Result<()> {
+    let input_file_path = matches.value_of("input").expect("input file compulsory");
+    let output_file_path = matches.value_of("output").expect("output file compulsory");
+    let reordered_file_path = matches.value_of("file").expect("file compulsory");
+    let config = ReorderConfig {
+        seed: matches.value_of_t("seed")?,
+        iterations: matches.value_of_t("iterations")?,
+    };
+    let output_data = load_output(output_file_path)?;
+    let precomputed = load_precomputed(input_file_path, false)?;
+    let reordered = reorder(&precomputed, &output_data, &config)?;
+    let mut writer = BufWriter::new(
+        File::create(reordered_file_path)
+            .with_context(|| format!("cannot create {}", reordered_file_path))?,
+    );
+    write_output(&reordered.output, &mut writer)?;
+    writer.flush()?;
+    println!(
+        "{} score: {} -> {} ({} moves)",
+        reordered_file_path, reordered.initial_score, reordered.score, reordered.moves
+    );
+    Ok(())
+}
+
+fn export_mip(matches: &ArgMatches) -> anyhow::Result<()> {
+    let input_file_path = matches.value_of("input").expect("input file compulsory");
+    let model_file_path = matches.value_of("file").expect("file compulsory");
+    let horizon = match matches.value_of("horizon") {
+        Some(_) => Some(matches.value_of_t("horizon")?),
+        None => None,
+    };
+    let input = parse_input(&read_content(input_file_path)?)?;
+    let model = build_model(&input, horizon)?;
+    let mut writer = BufWriter::new(
+        File::create(model_file_path)
+            .with_context(|| format!("cannot create {}", model_file_path))?,
+    );
+    match matches.value_of("format") {
+        Some("mps") => model.write_mps(&mut writer)?,
+        _ => model.write_lp(&mut writer)?,
+    }
+    writer.flush()?;
+    println!(
+        "{}: {} variables, {} constraints",
+        model_file_path,
+        model.n_variables(),
+        model.n_constraints()
+    );
+    Ok(())
+}
+
+fn import_mip(matches: &ArgMatches) -> anyhow::Result<()> {
+    let input_file_path = matches.value_of("input").expect("input file compulsory");
+    let solution_file_path = matches
+        .value_of("solution")
+        .expect("solution file compulsory");
+    let output_file_path = matches.value_of("file").expect("file compulsory");
+    let input = parse_input(&read_content(input_file_path)?)?;
+    let values = parse_solution(&read_content(solution_file_path)?)?;
+    let output_data = import_solution(&input, &values)?;
+    let mut writer = BufWriter::new(
+        File::create(output_file_path)
+            .with_context(|| format!("cannot create {}", output_file_path))?,
+    );
+    write_output(&output_data, &mut writer)?;
+    writer.flush()?;
+    let score = compute_score_precomputed(&mut precompute_from_input(&input), &output_data, false)?;
+    println!("{} score: {}", output_file_path, score);
+    Ok(())
+}
+
+fn validate_best_output(input_file_path: &str) -> anyhow::Result<ValidOutput> {
+    let dataset = dataset_name(input_file_path);
+    let path = best_output_path(Path::new(BEST_DIR), &dataset);
+    let output_file_path = path.to_string_lossy();
+    let (output_data, content) = load_output_and_content(&output_file_path)
+        .with_context(|| format!("could not load best output {}", output_file_path))?;
+    let mut precomputed = load_precomputed(input_file_path, false)?;
+    let score = compute_score_precomputed(&mut precomputed, &output_data, false)
+        .with_context(|| format!("invalid best output {}", output_file_path))?;
+    Ok(ValidOutput {
+        dataset,
+        content,
+        score,
+    })
+}
+
+fn package_best_outputs(matches: &ArgMatches) -> anyhow::Result<()> {
+    let input_files = matches.values_of("input").expect("input files compulsory");
+    let sources_dir = matches.value_of("sources").expect("sources compulsory");
+    let zip_path = matches.value_of("zip").expect("zip has a default value");
+    let mut outputs = Vec::with_capacity(input_files.len());
+    let mut invalid = 0;
+    for input_file_path in input_files {
+        match validate_best_output(input_file_path) {
+            Ok(output) => outputs.push(output),
+            Err(e) => {
+                invalid += 1;
+                eprintln!("{}: {:#}", input_file_path, e);
+            }
+        }
+    }
+    if invalid > 0 {
+        bail!(
+            "{} invalid or missing best outputs, nothing packaged",
+            invalid
+        );
+    }
+    let manifest = package(&outputs, Path::new(sources_dir), Path::new(zip_path))?;
+    for output in &manifest.outputs {
+        println!(
+            "{} score: {}",
+            output.output,
+            output.score.to_formatted_string(&Locale::en)
+        );
+    }
+    println!(
+        "total score: {}",
+        manifest.total_score.to_formatted_string(&Locale::en)
+    );
+    println!("packaged to {}", zip_path);
+    Ok(())
+}
+
+fn serve_datasets(matches: &ArgMatches) -> anyhow::Result<()> {
+    let input_files = matches.values_of("input").expect("input files compulsory");
+    let port: u16 = matches.value_of_t("port")?;
+    let workers: usize = matches.value_of_t("workers")?;
+    let leaderboard_dir = matches
+        .value_of("leaderboard")
+        .expect("leaderboard has a default value");
+    let leaderboard = Leaderboard::load(Path::new(leaderboard_dir))?;
+    let mut datasets = Datasets::default();
+    for input_file_path in input_files {
+        let dataset = dataset_name(input_file_path);
+        let precomputed = load_precomputed(input_file_path, false)?;
+        info!("serving {} as /score/{}", input_file_path, dataset);
+        let previous = datasets.insert(
+            dataset.clone(),
+            Dataset {
+                input_file_path: input_file_path.to_string(),
+                precomputed,
+            },
+        );
+        if previous.is_some() {
+            bail!("dataset {} provided twice", dataset);
+        }
+    }
+    serve(datasets, leaderboard, port, workers)
+}
+
+fn generate_input(matches: &ArgMatches) -> anyhow::Result<()> {
+    let config = GeneratorConfig {
+        seed: matches.value_of_t("seed")?,
+        contributors: matches.value_of_t("contributors")?,
+        projects: matches.value_of_t("projects")?,
+        skills: matches.value_of_t("skills")?,
+        max_skills_per_contributor: matches.value_of_t("max-skills")?,
+        max_roles: matches.value_of_t("max-roles")?,
+        max_level: matches.value_of_t("max-level")?,
+        levels: matches.value_of_t("levels")?,
+        max_days: matches.value_of_t("max-days")?,
+        max_score: matches.value_of_t("max-score")?,
+        deadline_tightness: matches.value_of_t("deadline-tightness")?,
+    };
+    let input = generate(&config)?;
+    let mut writer: Box<dyn Write> = match matches.value_of("output") {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).with_context(|| format!("cannot create {}", path))?,
+        )),
+        None => Box::new(BufWriter::new(std::io::stdout().lock())),
+    };
+    write_input(&input, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Arguments holding paths of files to read, where `-` is stdin.
+const READ_ARGS: [&str; 6] = [
+    "input", "output", "output-a", "output-b", "outputs", "solution",
+];
+
+/// Stdin can only be read once per run, whichever arguments it is given to.
+fn check_stdin_read_once(matches: &ArgMatches) -> anyhow::Result<()> {
+    let stdin_reads = READ_ARGS
+        .iter()
+        // not every subcommand has every argument
+        .filter_map(|arg| matches.try_get_raw(arg).ok().flatten())
+        .flatten()
+        .filter(|path| *path == STDIN)
+        .count();
+    if stdin_reads > 1 {
+        bail!("stdin ({}) can only be used once", STDIN);
+    }
+    Ok(())
+}
+
+fn main() -> anyhow::Result<()> {
+    // cf https://crates.io/crates/env_logger
+    env_logger::init();
+
+    // parse command line arguments
+    let matches = cli::get_command().get_matches();
+    check_stdin_read_once(matches.subcommand().map_or(&matches, |(_, m)| m))?;
+    match matches.subcommand() {
+        Some(("diff", sub_matches)) => diff(sub_matches),
+        Some(("explain", sub_matches)) => explain(sub_matches),
+        Some(("state", sub_matches)) => state(sub_matches),
+        Some(("curve", sub_matches)) => curve(sub_matches),
+        Some(("marginal", sub_matches)) => marginal(sub_matches),
+        Some(("slack", sub_matches)) => slack(sub_matches),
+        Some(("restaff", sub_matches)) => restaff_output(sub_matches),
+        Some(("repair", sub_matches)) => repair_output(sub_matches),
+        Some(("reorder", sub_matches)) => reorder_output(sub_matches),
+        Some(("mip-export", sub_matches)) => export_mip(sub_matches),
+        Some(("mip-import", sub_matches)) => import_mip(sub_matches),
+        Some(("package", sub_matches)) => package_best_outputs(sub_matches),
+        Some(("serve", sub_matches)) => serve_datasets(sub_matches),
+        Some(("generate", sub_matches)) => generate_input(sub_matches),
+        _ => score(&matches),
+    }
+}
@@ -0,0 +1,276 @@
+use crate::data::{Id, POutput};
+use crate::score::{
+    is_mentored, level_after, project_score, simulate, Event, Level, PreComputed, Score, Time,
+};
+use fxhash::FxHashMap;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+
+/// How the total score changes when a single planned project is removed from the output.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Marginal {
+    pub name: String,
+    pub score: Score,
+    /// total score without the project minus total score, `None` if the output becomes
+    /// invalid (a later contributor misses a level up they needed)
+    pub delta: Option<i64>,
+}
+
+struct Role {
+    contributor: Id,
+    skill: Id,
+    required: Level,
+}
+
+/// Simulated planned project, in output file order.
+struct Planned {
+    project: Id,
+    roles: Vec<Role>,
+    end: Time,
+    score: Score,
+}
+
+/// Whole output simulated once, then each removal only re-simulates the planned projects
+/// whose contributors availability or levels differ from this baseline.
+struct Baseline<'a> {
+    precomputed: &'a PreComputed,
+    planned: Vec<Planned>,
+    contributor_projects: Vec<Vec<usize>>, // contributor -> indices of their planned projects
+    level_ups: FxHashMap<(Id, Id), Vec<(usize, Level)>>, // (contributor, skill) -> (index, new level)
+}
+
+impl<'a> Baseline<'a> {
+    fn new(
+        precomputed: &'a PreComputed,
+        output: &POutput,
+        disable_checks: bool,
+    ) -> anyhow::Result<Self> {
+        let mut planned: Vec<Planned> = Vec::with_capacity(output.projects.len());
+        let mut level_ups: FxHashMap<(Id, Id), Vec<(usize, Level)>> = FxHashMap::default();
+        simulate(
+            &mut precomputed.clone(),
+            output,
+            disable_checks,
+            |event| match event {
+                Event::ProjectStarted { project, .. } => planned.push(Planned {
+                    project: *project,
+                    roles: vec![],
+                    end: 0,
+                    score: 0,
+                }),
+                Event::RoleFilled {
+                    contributor,
+                    skill,
+                    required,
+                    ..
+                } => {
+                    if let Some(p) = planned.last_mut() {
+                        p.roles.push(Role {
+                            contributor: *contributor,
+                            skill: *skill,
+                            required: *required,
+                        })
+                    }
+                }
+                Event::ProjectEnded { day, .. } => {
+                    if let Some(p) = planned.last_mut() {
+                        p.end = *day;
+                    }
+                }
+                Event::ScoreAwarded { score, .. } => {
+                    if let Some(p) = planned.last_mut() {
+                        p.score = *score;
+                    }
+                }
+                Event::LevelUp {
+                    contributor,
+                    skill,
+                    level,
+                    ..
+                } => level_ups
+                    .entry((*contributor, *skill))
+                    .or_default()
+                    .push((planned.len() - 1, *level)),
+            },
+        )?;
+        let mut contributor_projects = vec![vec![]; precomputed.contributors.len()];
+        for (index, p) in planned.iter().enumerate() {
+            for role in &p.roles {
+                contributor_projects[role.contributor].push(index);
+            }
+        }
+        Ok(Baseline {
+            precomputed,
+            planned,
+            contributor_projects,
+            level_ups,
+        })
+    }
+
+    /// Availability of `contributor` right before planned project `index`.
+    fn availability_before(&self, contributor: Id, index: usize) -> Time {
+        let projects = &self.contributor_projects[contributor];
+        match projects.partition_point(|p| *p < index) {
+            0 => self.precomputed.contributors[contributor].next_availability,
+            n => self.planned[projects[n - 1]].end,
+        }
+    }
+
+    /// Level of `contributor` in `skill` right before planned project `index`.
+    fn level_before(&self, contributor: Id, skill: Id, index: usize) -> Level {
+        let initial = || {
+            self.precomputed
+                .levels
+                .get(&(contributor, skill))
+                .cloned()
+                .unwrap_or(0)
+        };
+        match self.level_ups.get(&(contributor, skill)) {
+            Some(level_ups) => match level_ups.partition_point(|(p, _)| *p < index) {
+                0 => initial(),
+                n => level_ups[n - 1].1,
+            },
+            None => initial(),
+        }
+    }
+
+    /// First planned project of `contributor` after `index`.
+    fn next_project(&self, contributor: Id, index: usize) -> Option<usize> {
+        let projects = &self.contributor_projects[contributor];
+        projects
+            .get(projects.partition_point(|p| *p <= index))
+            .cloned()
+    }
+
+    /// Score delta when planned project `removed` is left out, `None` if the output becomes invalid.
+    fn without(&self, removed: usize, disable_checks: bool) -> Option<i64> {
+        // contributors whose state differs from the baseline, after their last simulated project
+        let mut availability: FxHashMap<Id, Time> = FxHashMap::default();
+        let mut levels: FxHashMap<Id, FxHashMap<Id, Level>> = FxHashMap::default(); // contributor -> skill -> level
+        let mut queue: BinaryHeap<Reverse<usize>> = BinaryHeap::new();
+
+        let mut delta = -(self.planned[removed].score as i64);
+        for role in &self.planned[removed].roles {
+            let c = role.contributor;
+            availability.insert(c, self.availability_before(c, removed));
+            let level = self.level_before(c, role.skill, removed);
+            if level != self.level_before(c, role.skill, removed + 1) {
+                levels.entry(c).or_default().insert(role.skill, level);
+            }
+            if let Some(next) = self.next_project(c, removed) {
+                queue.push(Reverse(next));
+            }
+        }
+
+        let mut last = removed;
+        while let Some(Reverse(index)) = queue.pop() {
+            if index == last {
+                continue;
+            }
+            last = index;
+            let planned = &self.planned[index];
+            let project = &self.precomputed.projects[planned.project];
+            let level = |c: Id, skill: Id| {
+                levels
+                    .get(&c)
+                    .and_then(|skills| skills.get(&skill))
+                    .cloned()
+                    .unwrap_or_else(|| self.level_before(c, skill, index))
+            };
+
+            let start = planned
+                .roles
+                .iter()
+                .map(|r| {
+                    availability
+                        .get(&r.contributor)
+                        .cloned()
+                        .unwrap_or_else(|| self.availability_before(r.contributor, index))
+                })
+                .max()
+                .unwrap_or(0);
+            let role_levels: Vec<Level> = planned
+                .roles
+                .iter()
+                .map(|r| level(r.contributor, r.skill))
+                .collect();
+            if !disable_checks {
+                for (role, own) in planned.roles.iter().zip(&role_levels) {
+                    let team_levels = planned
+                        .roles
+                        .iter()
+                        .map(|other| level(other.contributor, role.skill));
+                    if *own < role.required && !is_mentored(*own, role.required, team_levels) {
+                        return None;
+                    }
+                }
+            }
+
+            let (score, end) = project_score(start, project);
+            delta += score as i64 - planned.score as i64;
+
+            for (role, own) in planned.roles.iter().zip(role_levels) {
+                let c = role.contributor;
+                if end == planned.end {
+                    availability.remove(&c);
+                } else {
+                    availability.insert(c, end);
+                }
+                let level_after = level_after(own, role.required).unwrap_or(own);
+                if level_after == self.level_before(c, role.skill, index + 1) {
+                    if let Some(skills) = levels.get_mut(&c) {
+                        skills.remove(&role.skill);
+                        if skills.is_empty() {
+                            levels.remove(&c);
+                        }
+                    }
+                } else {
+                    levels.entry(c).or_default().insert(role.skill, level_after);
+                }
+            }
+            for role in &planned.roles {
+                let c = role.contributor;
+                if availability.contains_key(&c) || levels.contains_key(&c) {
+                    if let Some(next) = self.next_project(c, index) {
+                        queue.push(Reverse(next));
+                    }
+                }
+            }
+        }
+        Some(delta)
+    }
+}
+
+/// Leave-one-out score delta of every planned project, in output file order.
+pub fn leave_one_out(
+    precomputed: &PreComputed,
+    output: &POutput,
+    disable_checks: bool,
+) -> anyhow::Result<Vec<Marginal>> {
+    let baseline = Baseline::new(precomputed, output, disable_checks)?;
+    Ok(baseline
+        .planned
+        .iter()
+        .enumerate()
+        .map(|(index, planned)| Marginal {
+            name: precomputed.projects[planned.project].name.clone(),
+            score: planned.score,
+            delta: baseline.without(index, disable_checks),
+        })
+        .collect())
+}
+
+impl fmt::Display for Marginal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.delta {
+            Some(delta) => write!(f, "{}: {} ({:+} if removed)", self.name, self.score, delta),
+            None => write!(
+                f,
+                "{}: {} (required, later projects become invalid if removed)",
+                self.name, self.score
+            ),
+        }
+    }
+}
@@ -0,0 +1,472 @@
+//! Time-indexed MIP model of an input, for offline MIP solvers.
+//!
+//! Variables:
+//! * `x_<p>_<t>`: project `p` starts on day `t` (binary)
+//! * `y_<p>_<r>_<c>`: contributor `c` fills role `r` of project `p` (binary)
+//! * `w_<p>_<c>_<t>`: contributor `c` works on project `p` started on day `t`
+//!
+//! `p`, `r` and `c` are indices in the input file. Level ups are not modelled: a role can only
+//! be filled by a contributor with the required level in the input, or one level short with a
+//! mentor on the project. Every solution is then a valid output, scoring at least its objective.
+
+use crate::data::{Id, PInput, POutput, PPlannedProject};
+use crate::score::Time;
+use anyhow::{bail, Context};
+use fxhash::FxHashMap;
+use std::io::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Sense {
+    Le,
+    Eq,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Constraint {
+    name: String,
+    terms: Vec<(usize, i64)>, // (variable, coefficient)
+    sense: Sense,
+    rhs: i64,
+}
+
+/// Linear model with binary and non-negative continuous variables, maximizing the score.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Model {
+    variables: Vec<String>,
+    binary: Vec<bool>,
+    objective: Vec<(usize, i64)>,
+    constraints: Vec<Constraint>,
+}
+
+impl Model {
+    fn variable(&mut self, name: String, binary: bool) -> usize {
+        self.variables.push(name);
+        self.binary.push(binary);
+        self.variables.len() - 1
+    }
+
+    fn constraint(&mut self, name: String, terms: Vec<(usize, i64)>, sense: Sense, rhs: i64) {
+        self.constraints.push(Constraint {
+            name,
+            terms,
+            sense,
+            rhs,
+        });
+    }
+
+    pub fn n_variables(&self) -> usize {
+        self.variables.len()
+    }
+
+    pub fn n_constraints(&self) -> usize {
+        self.constraints.len()
+    }
+
+    /// Objective value of an assignment (missing variables are 0), fails on the first
+    /// violated constraint or non binary value of a binary variable.
+    pub fn objective_value(&self, values: &FxHashMap<String, f64>) -> anyhow::Result<i64> {
+        const EPSILON: f64 = 1e-6;
+        let value: Vec<f64> = self
+            .variables
+            .iter()
+            .map(|name| values.get(name).cloned().unwrap_or(0.0))
+            .collect();
+        for (i, v) in value.iter().enumerate() {
+            if *v < -EPSILON || (self.binary[i] && (*v - v.round()).abs() > EPSILON) {
+                bail!("{} = {} is out of bounds", self.variables[i], v);
+            }
+        }
+        for constraint in &self.constraints {
+            let lhs: f64 = constraint
+                .terms
+                .iter()
+                .map(|(v, coefficient)| *coefficient as f64 * value[*v])
+                .sum();
+            let rhs = constraint.rhs as f64;
+            let satisfied = match constraint.sense {
+                Sense::Le => lhs <= rhs + EPSILON,
+                Sense::Eq => (lhs - rhs).abs() <= EPSILON,
+                Sense::Ge => lhs >= rhs - EPSILON,
+            };
+            if !satisfied {
+                bail!("constraint {} is violated", constraint.name);
+            }
+        }
+        Ok(self
+            .objective
+            .iter()
+            .map(|(v, coefficient)| *coefficient as f64 * value[*v])
+            .sum::<f64>()
+            .round() as i64)
+    }
+
+    fn write_terms<W: Write>(&self, terms: &[(usize, i64)], writer: &mut W) -> std::io::Result<()> {
+        for (i, (variable, coefficient)) in terms.iter().enumerate() {
+            // keep lines short, some solvers limit their length
+            if i > 0 && i % 8 == 0 {
+                write!(writer, "\n   ")?;
+            }
+            let sign = if *coefficient < 0 { "-" } else { "+" };
+            write!(
+                writer,
+                " {} {} {}",
+                sign,
+                coefficient.abs(),
+                self.variables[*variable]
+            )?;
+        }
+        Ok(())
+    }
+
+    /// CPLEX LP format.
+    pub fn write_lp<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writeln!(
+            writer,
+            "\\ Google Hashcode 2022, see mip.rs for the variables"
+        )?;
+        writeln!(writer, "Maximize")?;
+        write!(writer, " score:")?;
+        self.write_terms(&self.objective, writer)?;
+        writeln!(writer)?;
+        writeln!(writer, "Subject To")?;
+        for constraint in &self.constraints {
+            write!(writer, " {}:", constraint.name)?;
+            self.write_terms(&constraint.terms, writer)?;
+            let sense = match constraint.sense {
+                Sense::Le => "<=",
+                Sense::Eq => "=",
+                Sense::Ge => ">=",
+            };
+            writeln!(writer, " {} {}", sense, constraint.rhs)?;
+        }
+        writeln!(writer, "Binaries")?;
+        for (variable, binary) in self.variables.iter().zip(&self.binary) {
+            if *binary {
+                writeln!(writer, " {}", variable)?;
+            }
+        }
+        writeln!(writer, "End")
+    }
+
+    /// Free MPS format, the objective is negated to be minimized.
+    pub fn write_mps<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let mut columns: Vec<Vec<(&str, i64)>> = vec![vec![]; self.variables.len()];
+        for (variable, coefficient) in &self.objective {
+            columns[*variable].push(("score", -coefficient));
+        }
+        for constraint in &self.constraints {
+            for (variable, coefficient) in &constraint.terms {
+                columns[*variable].push((&constraint.name, *coefficient));
+            }
+        }
+        writeln!(
+            writer,
+            "* Google Hashcode 2022, see mip.rs for the variables"
+        )?;
+        writeln!(writer, "* objective is minus the score")?;
+        writeln!(writer, "NAME hashcode")?;
+        writeln!(writer, "ROWS")?;
+        writeln!(writer, " N score")?;
+        for constraint in &self.constraints {
+            let sense = match constraint.sense {
+                Sense::Le => "L",
+                Sense::Eq => "E",
+                Sense::Ge => "G",
+            };
+            writeln!(writer, " {} {}", sense, constraint.name)?;
+        }
+        writeln!(writer, "COLUMNS")?;
+        for (variable, column) in self.variables.iter().zip(&columns) {
+            for (row, coefficient) in column {
+                writeln!(writer, " {} {} {}", variable, row, coefficient)?;
+            }
+        }
+        writeln!(writer, "RHS")?;
+        for constraint in &self.constraints {
+            if constraint.rhs != 0 {
+                writeln!(writer, " RHS {} {}", constraint.name, constraint.rhs)?;
+            }
+        }
+        writeln!(writer, "BOUNDS")?;
+        for (variable, binary) in self.variables.iter().zip(&self.binary) {
+            if *binary {
+                writeln!(writer, " BV BND {}", variable)?;
+            }
+        }
+        writeln!(writer, "ENDATA")
+    }
+}
+
+/// Largest number of projects times days in the horizon `build_model` accepts, the model
+/// has variables for every project, day and candidate contributor.
+pub const MAX_PROJECT_DAYS: usize = 100_000;
+
+/// Time-indexed model of `input`, with every project ending by `horizon`.
+///
+/// Projects only start on days where they still earn points. By default the horizon is the sum
+/// of all projects duration: starting every project as early as possible, no project ends later.
+/// The model grows with the number of days, it fails above `MAX_PROJECT_DAYS`: keep it for small
+/// inputs or pass a shorter horizon.
+pub fn build_model(input: &PInput, horizon: Option<Time>) -> anyhow::Result<Model> {
+    let mut model = Model::default();
+    let levels: Vec<FxHashMap<&str, usize>> = input
+        .contributors
+        .iter()
+        .map(|c| {
+            c.skills
+                .iter()
+                .map(|s| (s.name.as_str(), s.level))
+                .collect()
+        })
+        .collect();
+    let level = |contributor: Id, skill: &str| levels[contributor].get(skill).cloned().unwrap_or(0);
+    let horizon = horizon.unwrap_or_else(|| {
+        input.projects.iter().fold(0, |total: Time, p| {
+            total.saturating_add(p.days_to_completion)
+        })
+    });
+    if input.projects.len().saturating_mul(horizon) > MAX_PROJECT_DAYS {
+        bail!(
+            "{} projects over {} days is too large a model (more than {} project days), pass a shorter horizon (--horizon)",
+            input.projects.len(),
+            horizon,
+            MAX_PROJECT_DAYS
+        );
+    }
+
+    // contributor -> (project, start day, w variable)
+    let mut works: Vec<Vec<(usize, Time, usize)>> = vec![vec![]; input.contributors.len()];
+    for (p, project) in input.projects.iter().enumerate() {
+        // latest start still earning points and ending by the horizon
+        let last_start = match (
+            (project.best_before.saturating_add(project.score))
+                .checked_sub(project.days_to_completion.saturating_add(1)),
+            horizon.checked_sub(project.days_to_completion),
+        ) {
+            (Some(last_start), Some(latest)) => last_start.min(latest),
+            _ => continue,
+        };
+
+        // contributors with the level, or one level short if someone for another role could mentor them
+        let one_short =
+            |c: Id, skill: &str, required: usize| level(c, skill).saturating_add(1) >= required;
+        let eligible: Vec<Vec<Id>> = project
+            .skills
+            .iter()
+            .enumerate()
+            .map(|(r, skill)| {
+                let can_mentor = |c: Id| {
+                    level(c, &skill.name) >= skill.level
+                        && project
+                            .skills
+                            .iter()
+                            .enumerate()
+                            .any(|(other, o)| other != r && one_short(c, &o.name, o.level))
+                };
+                (0..input.contributors.len())
+                    .filter(|c| {
+                        level(*c, &skill.name) >= skill.level
+                            || (one_short(*c, &skill.name, skill.level)
+                                && (0..input.contributors.len()).any(|m| m != *c && can_mentor(m)))
+                    })
+                    .collect()
+            })
+            .collect();
+        if eligible.iter().any(|candidates| candidates.is_empty()) {
+            continue;
+        }
+
+        let starts: Vec<usize> = (0..=last_start)
+            .map(|t| {
+                let x = model.variable(format!("x_{}_{}", p, t), true);
+                let late = (t + project.days_to_completion).saturating_sub(project.best_before);
+                model.objective.push((x, (project.score - late) as i64));
+                x
+            })
+            .collect();
+        model.constraint(
+            format!("once_{}", p),
+            starts.iter().map(|x| (*x, 1)).collect(),
+            Sense::Le,
+            1,
+        );
+
+        // roles[r] = (contributor, y variable)
+        let roles: Vec<Vec<(Id, usize)>> = eligible
+            .iter()
+            .enumerate()
+            .map(|(r, candidates)| {
+                candidates
+                    .iter()
+                    .map(|c| (*c, model.variable(format!("y_{}_{}_{}", p, r, c), true)))
+                    .collect()
+            })
+            .collect();
+        for (r, candidates) in roles.iter().enumerate() {
+            let mut terms: Vec<(usize, i64)> = candidates.iter().map(|(_, y)| (*y, 1)).collect();
+            terms.extend(starts.iter().map(|x| (*x, -1)));
+            model.constraint(format!("fill_{}_{}", p, r), terms, Sense::Eq, 0);
+        }
+        // mentored contributors need someone with the required level on another role
+        for (r, (skill, candidates)) in project.skills.iter().zip(&roles).enumerate() {
+            for (c, y) in candidates {
+                if level(*c, &skill.name) >= skill.level {
+                    continue;
+                }
+                let mut terms = vec![(*y, 1)];
+                for (other, other_candidates) in roles.iter().enumerate() {
+                    if other != r {
+                        terms.extend(
+                            other_candidates
+                                .iter()
+                                .filter(|(mentor, _)| level(*mentor, &skill.name) >= skill.level)
+                                .map(|(_, y)| (*y, -1)),
+                        );
+                    }
+                }
+                model.constraint(format!("mentor_{}_{}_{}", p, r, c), terms, Sense::Le, 0);
+            }
+        }
+
+        let mut team: FxHashMap<Id, Vec<usize>> = FxHashMap::default(); // contributor -> y variables
+        for candidates in &roles {
+            for (c, y) in candidates {
+                team.entry(*c).or_default().push(*y);
+            }
+        }
+        let mut team: Vec<(Id, Vec<usize>)> = team.into_iter().collect();
+        team.sort_unstable();
+        for (c, ys) in team {
+            if ys.len() > 1 {
+                model.constraint(
+                    format!("one_role_{}_{}", p, c),
+                    ys.iter().map(|y| (*y, 1)).collect(),
+                    Sense::Le,
+                    1,
+                );
+            }
+            // w >= x + sum(y) - 1, only bounded from below since it is only limited by `busy`
+            for (t, x) in starts.iter().enumerate() {
+                let w = model.variable(format!("w_{}_{}_{}", p, c, t), false);
+                let mut terms = vec![(w, 1), (*x, -1)];
+                terms.extend(ys.iter().map(|y| (*y, -1)));
+                model.constraint(format!("works_{}_{}_{}", p, c, t), terms, Sense::Ge, -1);
+                works[c].push((p, t, w));
+            }
+        }
+    }
+
+    // a contributor works on one project at a time
+    for (c, works) in works.iter().enumerate() {
+        let mut busy: Vec<Vec<(usize, i64)>> = vec![vec![]; horizon];
+        for (p, t, w) in works {
+            for terms in busy
+                .iter_mut()
+                .skip(*t)
+                .take(input.projects[*p].days_to_completion)
+            {
+                terms.push((*w, 1));
+            }
+        }
+        for (day, terms) in busy.into_iter().enumerate() {
+            if terms.len() > 1 {
+                model.constraint(format!("busy_{}_{}", c, day), terms, Sense::Le, 1);
+            }
+        }
+    }
+    Ok(model)
+}
+
+/// Variables values from a solver solution file, `x_` and `y_` variables only.
+///
+/// Reads any format with the variable name followed by its value on the same line (Gurobi,
+/// HiGHS, SCIP, CBC...).
+pub fn parse_solution(solution: &str) -> anyhow::Result<FxHashMap<String, f64>> {
+    let mut values = FxHashMap::default();
+    for line in solution.lines() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        for pair in tokens.windows(2) {
+            if pair[0].starts_with("x_") || pair[0].starts_with("y_") {
+                let value: f64 = pair[1]
+                    .parse()
+                    .with_context(|| format!("invalid value for {}: {}", pair[0], pair[1]))?;
+                values.insert(pair[0].to_string(), value);
+                break;
+            }
+        }
+    }
+    Ok(values)
+}
+
+fn indices(name: &str, expected: usize) -> anyhow::Result<Vec<usize>> {
+    let indices: Vec<usize> = name
+        .split('_')
+        .skip(1)
+        .map(|i| i.parse())
+        .collect::<Result<_, _>>()
+        .with_context(|| format!("invalid variable name {}", name))?;
+    if indices.len() != expected {
+        bail!("invalid variable name {}", name);
+    }
+    Ok(indices)
+}
+
+/// Output from the variables of a solution, projects ordered by start day.
+pub fn import_solution(input: &PInput, values: &FxHashMap<String, f64>) -> anyhow::Result<POutput> {
+    let mut starts: Vec<Option<Time>> = vec![None; input.projects.len()];
+    let mut roles: Vec<Vec<Option<Id>>> = input
+        .projects
+        .iter()
+        .map(|p| vec![None; p.skills.len()])
+        .collect();
+    for (name, value) in values {
+        if *value < 0.5 {
+            continue;
+        }
+        if name.starts_with("x_") {
+            let i = indices(name, 2)?;
+            match starts.get_mut(i[0]) {
+                Some(Some(_)) => bail!("project {} starts more than once", i[0]),
+                Some(start) => *start = Some(i[1]),
+                None => bail!("unknown project {} in {}", i[0], name),
+            }
+        } else if name.starts_with("y_") {
+            let i = indices(name, 3)?;
+            if i[2] >= input.contributors.len() {
+                bail!("unknown contributor {} in {}", i[2], name);
+            }
+            match roles.get_mut(i[0]).and_then(|r| r.get_mut(i[1])) {
+                Some(Some(_)) => {
+                    bail!("role {} of project {} is filled more than once", i[1], i[0])
+                }
+                Some(role) => *role = Some(i[2]),
+                None => bail!("unknown role {} of project {} in {}", i[1], i[0], name),
+            }
+        }
+    }
+    let mut planned: Vec<(Time, usize)> = starts
+        .iter()
+        .enumerate()
+        .filter_map(|(p, start)| start.map(|t| (t, p)))
+        .collect();
+    planned.sort_unstable();
+    let mut projects = Vec::with_capacity(planned.len());
+    for (_, p) in planned {
+        let mut contributor_names = vec![];
+        for (r, role) in roles[p].iter().enumerate() {
+            match role {
+                Some(c) => contributor_names.push(input.contributors[*c].name.clone()),
+                None => bail!("role {} of project {} is not filled", r, p),
+            }
+        }
+        projects.push(PPlannedProject {
+            name: input.projects[p].name.clone(),
+            contributor_names,
+        });
+    }
+    Ok(POutput {
+        n_projects: projects.len(),
+        projects,
+    })
+}
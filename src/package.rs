@@ -0,0 +1,109 @@
+use crate::score::Score;
+use anyhow::bail;
+use fxhash::FxHashSet;
+use log::{debug, info};
+use serde::Serialize;
+use std::fs::{read_dir, File};
+use std::io::{copy, Write};
+use std::path::{Path, PathBuf};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+pub const SOURCES_DIR: &str = "sources";
+pub const MANIFEST: &str = "manifest.json";
+
+#[derive(Serialize, Debug)]
+pub struct PackagedOutput {
+    pub dataset: String,
+    pub output: String, // path inside the zip
+    pub score: Score,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct Manifest {
+    pub outputs: Vec<PackagedOutput>,
+    pub total_score: Score,
+}
+
+/// Output validated with checks enabled, ready to be packaged.
+pub struct ValidOutput {
+    pub dataset: String,
+    pub content: String, // the text that was validated, packaged as is
+    pub score: Score,
+}
+
+fn is_ignored(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|f| f.to_str())
+        .is_some_and(|f| f.starts_with('.') || f == "target")
+}
+
+/// Source files under `dir`, skipping hidden files, `target` directories and `exclude`.
+fn source_files(dir: &Path, exclude: &Path, files: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    let mut entries = read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|e| e.path()); // reproducible archives
+    for entry in entries {
+        let path = entry.path();
+        if is_ignored(&path) || path.canonicalize()? == exclude {
+            debug!("skipping {}", path.display());
+        } else if entry.file_type()?.is_dir() {
+            source_files(&path, exclude, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Writes a zip with one output per dataset, the sources and a manifest with expected scores.
+///
+/// Fails before writing anything if a dataset is provided twice.
+pub fn package(
+    outputs: &[ValidOutput],
+    sources_dir: &Path,
+    zip_path: &Path,
+) -> anyhow::Result<Manifest> {
+    let mut datasets = FxHashSet::default();
+    for output in outputs {
+        if !datasets.insert(&output.dataset) {
+            bail!("dataset {} provided twice", output.dataset);
+        }
+    }
+    let mut zip = ZipWriter::new(File::create(zip_path)?);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut manifest = Manifest::default();
+    for output in outputs {
+        let name = format!("{}.out", output.dataset);
+        zip.start_file(&name, options)?;
+        zip.write_all(output.content.as_bytes())?;
+        manifest.total_score += output.score;
+        manifest.outputs.push(PackagedOutput {
+            dataset: output.dataset.clone(),
+            output: name,
+            score: output.score,
+        });
+    }
+
+    let mut files = vec![];
+    // the zip itself may be written inside the sources directory
+    source_files(sources_dir, &zip_path.canonicalize()?, &mut files)?;
+    for file in &files {
+        let relative = file.strip_prefix(sources_dir)?;
+        let name = Path::new(SOURCES_DIR).join(relative);
+        // zip entries always use '/' as separator
+        let name = name
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        info!("adding {}", name);
+        zip.start_file(name, options)?;
+        copy(&mut File::open(file)?, &mut zip)?;
+    }
+
+    zip.start_file(MANIFEST, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    zip.finish()?;
+    Ok(manifest)
+}
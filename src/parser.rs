@@ -1,16 +1,18 @@
 use anyhow::bail;
 use nom::bytes::complete::{take_while1, take_while_m_n};
 use nom::combinator::map_res;
-use nom::error::{convert_error, VerboseError};
-use nom::multi::{many_m_n, separated_list1};
+use nom::error::{convert_error, ErrorKind, ParseError, VerboseError};
+use nom::multi::separated_list1;
 use nom::sequence::{terminated, tuple};
 use nom::IResult;
 
-use crate::data::{PContributor, PContributorSkill, PInput, POutput, PPlannedProject, PProject};
+use crate::data::{
+    PContributor, PContributorSkill, PInput, POutput, PPlannedProject, PProject, MAX_PREALLOCATION,
+};
 use nom::character::complete::{digit1, line_ending};
 
-pub(crate) type N = usize;
-pub(crate) type Res<T, U> = IResult<T, U, VerboseError<T>>;
+pub type N = usize;
+pub type Res<T, U> = IResult<T, U, VerboseError<T>>;
 
 fn number(input: &str) -> Res<&str, &str> {
     digit1(input)
@@ -20,6 +22,24 @@ fn positive_number(input: &str) -> Res<&str, N> {
     map_res(number, |out| N::from_str_radix(out, 10))(input)
 }
 
+/// Applies `parser` exactly `n` times, like `many_m_n(n, n, parser)` but without
+/// preallocating `n` elements since `n` comes from the file being parsed.
+fn exactly<'a, O>(
+    n: usize,
+    mut parser: impl FnMut(&'a str) -> Res<&'a str, O>,
+) -> impl FnMut(&'a str) -> Res<&'a str, Vec<O>> {
+    move |mut input: &'a str| {
+        let mut res = Vec::with_capacity(n.min(MAX_PREALLOCATION));
+        for _ in 0..n {
+            let (tail, value) = parser(input)
+                .map_err(|err| err.map(|e| VerboseError::append(input, ErrorKind::Count, e)))?;
+            res.push(value);
+            input = tail;
+        }
+        Ok((input, res))
+    }
+}
+
 fn single_space(input: &str) -> Res<&str, &str> {
     take_while_m_n(1, 1, |c: char| c == ' ')(input)
 }
@@ -45,11 +65,7 @@ fn contributor(input: &str) -> Res<&str, PContributor> {
         tuple((non_space_or_unix_eol, single_space, positive_number)),
         line_ending,
     )(input)?;
-    let (i, skills) = many_m_n(
-        n_skills,
-        n_skills,
-        terminated(contributor_skill, line_ending),
-    )(i)?;
+    let (i, skills) = exactly(n_skills, terminated(contributor_skill, line_ending))(i)?;
     Ok((
         i,
         PContributor {
@@ -67,7 +83,7 @@ fn project(input: &str) -> Res<&str, PProject> {
     let (i, (score, _)) = tuple((positive_number, single_space))(i)?;
     let (i, (best_before, _)) = tuple((positive_number, single_space))(i)?;
     let (i, n_roles) = terminated(positive_number, line_ending)(i)?;
-    let (i, skills) = many_m_n(n_roles, n_roles, terminated(contributor_skill, line_ending))(i)?;
+    let (i, skills) = exactly(n_roles, terminated(contributor_skill, line_ending))(i)?;
     Ok((
         i,
         PProject {
@@ -87,12 +103,12 @@ fn _parse_input(input: &str) -> Res<&str, PInput> {
         tuple((positive_number, single_space, positive_number)),
         line_ending,
     )(input)?;
-    let (i, mut contributors) = many_m_n(n_contributors, n_contributors, contributor)(i)?;
+    let (i, mut contributors) = exactly(n_contributors, contributor)(i)?;
     for (id, c) in contributors.iter_mut().enumerate() {
         c.id = id;
     }
-    let (i, mut projects) = many_m_n(n_projects, n_projects, project)(i)?;
-    for (id, mut p) in projects.iter_mut().enumerate() {
+    let (i, mut projects) = exactly(n_projects, project)(i)?;
+    for (id, p) in projects.iter_mut().enumerate() {
         p.id = id;
     }
     Ok((
@@ -109,8 +125,10 @@ fn _parse_input(input: &str) -> Res<&str, PInput> {
 pub fn parse_input(s: &str) -> anyhow::Result<PInput> {
     match _parse_input(s) {
         Ok((_, data)) => Ok(data),
-        Err(nom::Err::Error(err)) => bail!("{}", convert_error(s, err.clone())),
-        _ => unreachable!(),
+        Err(nom::Err::Error(err)) | Err(nom::Err::Failure(err)) => {
+            bail!("{}", convert_error(s, err))
+        }
+        Err(nom::Err::Incomplete(_)) => bail!("unexpected end of file"),
     }
 }
 
@@ -131,7 +149,7 @@ fn planned_project(input: &str) -> Res<&str, PPlannedProject> {
 
 fn _parse_output(input: &str) -> Res<&str, POutput> {
     let (i, n_projects) = terminated(positive_number, line_ending)(input)?;
-    let (i, projects) = many_m_n(n_projects, n_projects, planned_project)(i)?;
+    let (i, projects) = exactly(n_projects, planned_project)(i)?;
     Ok((
         i,
         POutput {
@@ -144,7 +162,9 @@ fn _parse_output(input: &str) -> Res<&str, POutput> {
 pub fn parse_output(s: &str) -> anyhow::Result<POutput> {
     match _parse_output(s) {
         Ok((_, data)) => Ok(data),
-        Err(nom::Err::Error(err)) => bail!("{}", convert_error(s, err.clone())),
-        _ => unreachable!(),
+        Err(nom::Err::Error(err)) | Err(nom::Err::Failure(err)) => {
+            bail!("{}", convert_error(s, err))
+        }
+        Err(nom::Err::Incomplete(_)) => bail!("unexpected end of file"),
     }
 }
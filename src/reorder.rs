@@ -0,0 +1,198 @@
+use crate::data::{Id, POutput};
+use crate::score::{
+    compute_project_scores_precomputed, simulate, Event, PreComputed, ProjectScore, Score,
+};
+use anyhow::Context;
+use fxhash::FxHashMap;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReorderConfig {
+    pub seed: u64,
+    /// number of moves tried, each one simulates the whole output
+    pub iterations: usize,
+}
+
+impl Default for ReorderConfig {
+    fn default() -> Self {
+        ReorderConfig {
+            seed: 0,
+            iterations: 1000,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Reordered {
+    pub output: POutput,
+    pub initial_score: Score,
+    pub score: Score,
+    pub moves: usize, // accepted moves
+}
+
+/// Earlier planned projects each planned project must stay after, indices in output file order.
+///
+/// Project `j` depends on project `i` when a contributor of `j` levels up on `i` in a skill
+/// `j` requires, whoever fills that role: they may need the level themselves or to mentor.
+pub fn dependencies(
+    precomputed: &PreComputed,
+    output: &POutput,
+) -> anyhow::Result<Vec<Vec<usize>>> {
+    let mut teams: Vec<Vec<Id>> = vec![];
+    let mut skills: Vec<Vec<Id>> = vec![];
+    let mut level_ups: FxHashMap<(Id, Id), Vec<usize>> = FxHashMap::default(); // (contributor, skill) -> projects
+    let mut dependencies: Vec<Vec<usize>> = vec![];
+    simulate(
+        &mut precomputed.clone(),
+        output,
+        true,
+        |event| match event {
+            Event::ProjectStarted { .. } => {
+                teams.push(vec![]);
+                skills.push(vec![]);
+                dependencies.push(vec![]);
+            }
+            Event::RoleFilled {
+                contributor, skill, ..
+            } => {
+                if let (Some(team), Some(skills)) = (teams.last_mut(), skills.last_mut()) {
+                    team.push(*contributor);
+                    skills.push(*skill);
+                }
+            }
+            Event::ProjectEnded { .. } => {
+                // before this project level ups
+                if let (Some(team), Some(skills), Some(depends_on)) =
+                    (teams.last(), skills.last(), dependencies.last_mut())
+                {
+                    for contributor in team {
+                        for skill in skills {
+                            if let Some(projects) = level_ups.get(&(*contributor, *skill)) {
+                                depends_on.extend(projects);
+                            }
+                        }
+                    }
+                    depends_on.sort_unstable();
+                    depends_on.dedup();
+                }
+            }
+            Event::LevelUp {
+                contributor, skill, ..
+            } => level_ups
+                .entry((*contributor, *skill))
+                .or_default()
+                .push(teams.len() - 1),
+            _ => {}
+        },
+    )?;
+    Ok(dependencies)
+}
+
+fn score_of(project_scores: &[ProjectScore]) -> Score {
+    project_scores.iter().map(|p| p.score).sum()
+}
+
+/// Hill climbing over the order of planned projects, teams are kept as they are.
+///
+/// Each move takes a project, a late one half of the time, and inserts it at a random position
+/// that keeps it after its `dependencies` and before the projects depending on it. The move is
+/// kept if the output is still valid and scores at least as much, moves that do not change the
+/// score let the search cross plateaus. `output` must be valid.
+pub fn reorder(
+    precomputed: &PreComputed,
+    output: &POutput,
+    config: &ReorderConfig,
+) -> anyhow::Result<Reordered> {
+    let mut project_scores =
+        compute_project_scores_precomputed(&mut precomputed.clone(), output, false)
+            .context("cannot reorder an invalid output, repair it first")?;
+    let initial_score = score_of(&project_scores);
+    let mut score = initial_score;
+    let depends_on = dependencies(precomputed, output)?;
+    let mut dependents: Vec<Vec<usize>> = vec![vec![]; depends_on.len()];
+    for (project, dependencies) in depends_on.iter().enumerate() {
+        for dependency in dependencies {
+            dependents[*dependency].push(project);
+        }
+    }
+
+    // order[position] = index of the project in `output`
+    let mut order: Vec<usize> = (0..output.projects.len()).collect();
+    let mut position: Vec<usize> = order.clone();
+    let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+    let mut moves = 0;
+    if order.len() < 2 {
+        return Ok(Reordered {
+            output: output.clone(),
+            initial_score,
+            score,
+            moves,
+        });
+    }
+    for _ in 0..config.iterations {
+        let late: Vec<usize> = project_scores
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.end > p.best_before)
+            .map(|(i, _)| i)
+            .collect();
+        let from = if !late.is_empty() && rng.gen_bool(0.5) {
+            late[rng.gen_range(0..late.len())]
+        } else {
+            rng.gen_range(0..order.len())
+        };
+        let project = order[from];
+        let earliest = depends_on[project]
+            .iter()
+            .map(|d| position[*d] + 1)
+            .max()
+            .unwrap_or(0);
+        let latest = dependents[project]
+            .iter()
+            .map(|d| position[*d] - 1)
+            .min()
+            .unwrap_or(order.len() - 1);
+        if earliest == latest {
+            continue;
+        }
+        let to = rng.gen_range(earliest..=latest);
+        if to == from {
+            continue;
+        }
+
+        let mut candidate_order = order.clone();
+        let moved = candidate_order.remove(from);
+        candidate_order.insert(to, moved);
+        let candidate = POutput {
+            n_projects: output.n_projects,
+            projects: candidate_order
+                .iter()
+                .map(|i| output.projects[*i].clone())
+                .collect(),
+        };
+        if let Ok(candidate_scores) =
+            compute_project_scores_precomputed(&mut precomputed.clone(), &candidate, false)
+        {
+            let candidate_score = score_of(&candidate_scores);
+            if candidate_score >= score {
+                score = candidate_score;
+                project_scores = candidate_scores;
+                order = candidate_order;
+                for (p, i) in order.iter().enumerate() {
+                    position[*i] = p;
+                }
+                moves += 1;
+            }
+        }
+    }
+    Ok(Reordered {
+        output: POutput {
+            n_projects: output.n_projects,
+            projects: order.iter().map(|i| output.projects[*i].clone()).collect(),
+        },
+        initial_score,
+        score,
+        moves,
+    })
+}
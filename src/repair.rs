@@ -0,0 +1,248 @@
+use crate::assign::{play_project, restaff_project, Objective, Restaffed, Staffing};
+use crate::data::{Id, POutput, PPlannedProject};
+use crate::marginal::leave_one_out;
+use crate::score::{compute_score_precomputed, PreComputed, Score};
+use fxhash::FxHashSet;
+use serde::Serialize;
+use std::fmt;
+
+/// Change made to an output by `repair`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub enum Change {
+    UnknownProject {
+        project: String,
+    },
+    DuplicateProject {
+        project: String,
+    },
+    UnknownContributor {
+        project: String,
+        contributor: String,
+    },
+    DuplicateContributor {
+        project: String,
+        contributor: String,
+    },
+    /// contributors added or removed so that each role has one, before restaffing
+    RoleCount {
+        project: String,
+        roles: usize,
+        contributors: usize,
+    },
+    Restaffed(Restaffed),
+    /// not enough contributors or not skilled enough, and the project cannot be restaffed
+    Dropped {
+        project: String,
+    },
+    /// valid but removing it increases the total score
+    Harmful {
+        project: String,
+        gain: Score,
+    },
+}
+
+#[derive(Serialize, Debug)]
+pub struct Repair {
+    #[serde(skip)]
+    pub output: POutput,
+    pub score: Score,
+    pub changes: Vec<Change>,
+}
+
+/// Drops unknown and duplicate projects and contributors, and makes each project have as
+/// many contributors as roles, the output may still have contributors not skilled enough.
+fn fix_structure(
+    precomputed: &PreComputed,
+    output: &POutput,
+    changes: &mut Vec<Change>,
+) -> POutput {
+    let mut planned: FxHashSet<Id> = FxHashSet::default();
+    let mut projects = vec![];
+    for project in &output.projects {
+        let project_id = match precomputed.projects_id.get(&project.name) {
+            Some(project_id) => *project_id,
+            None => {
+                changes.push(Change::UnknownProject {
+                    project: project.name.clone(),
+                });
+                continue;
+            }
+        };
+        if !planned.insert(project_id) {
+            changes.push(Change::DuplicateProject {
+                project: project.name.clone(),
+            });
+            continue;
+        }
+        let mut team: Vec<Id> = vec![];
+        for name in &project.contributor_names {
+            match precomputed.contributors_id.get(name) {
+                Some(c) if team.contains(c) => changes.push(Change::DuplicateContributor {
+                    project: project.name.clone(),
+                    contributor: name.clone(),
+                }),
+                Some(c) => team.push(*c),
+                None => changes.push(Change::UnknownContributor {
+                    project: project.name.clone(),
+                    contributor: name.clone(),
+                }),
+            }
+        }
+        let roles = precomputed.projects[project_id].skills.len();
+        if team.len() != roles {
+            changes.push(Change::RoleCount {
+                project: project.name.clone(),
+                roles,
+                contributors: team.len(),
+            });
+            team.truncate(roles);
+            // placeholders, restaffed later if not skilled enough
+            for c in 0..precomputed.contributors.len() {
+                if team.len() == roles {
+                    break;
+                }
+                if !team.contains(&c) {
+                    team.push(c);
+                }
+            }
+            if team.len() < roles {
+                changes.push(Change::Dropped {
+                    project: project.name.clone(),
+                });
+                continue;
+            }
+        }
+        projects.push(PPlannedProject {
+            name: project.name.clone(),
+            contributor_names: team
+                .iter()
+                .map(|c| precomputed.contributors[*c].name.clone())
+                .collect(),
+        });
+    }
+    POutput {
+        n_projects: projects.len(),
+        projects,
+    }
+}
+
+fn without(output: &POutput, project: &str) -> POutput {
+    let projects: Vec<PPlannedProject> = output
+        .projects
+        .iter()
+        .filter(|p| p.name != project)
+        .cloned()
+        .collect();
+    POutput {
+        n_projects: projects.len(),
+        projects,
+    }
+}
+
+/// Highest scoring valid output found from an invalid one, and what was changed.
+///
+/// Unknown and duplicate names are removed, projects with a contributor not skilled enough
+/// are restaffed (see `assign::restaff_project`) or dropped in a single pass, then the project
+/// whose removal increases the score most (see `marginal::leave_one_out`) is dropped until
+/// none does.
+pub fn repair(
+    precomputed: &PreComputed,
+    output: &POutput,
+    objective: Objective,
+) -> anyhow::Result<Repair> {
+    let mut changes = vec![];
+    let output = fix_structure(precomputed, output, &mut changes);
+
+    let mut state = precomputed.clone();
+    let mut projects = Vec::with_capacity(output.projects.len());
+    for mut planned_project in output.projects {
+        match restaff_project(&state, &planned_project, objective)? {
+            Staffing::Qualified => {}
+            Staffing::Restaffed(restaffed) => {
+                planned_project.contributor_names = restaffed.after.clone();
+                changes.push(Change::Restaffed(restaffed));
+            }
+            Staffing::Unstaffable => {
+                changes.push(Change::Dropped {
+                    project: planned_project.name,
+                });
+                continue;
+            }
+        }
+        play_project(&mut state, &planned_project)?;
+        projects.push(planned_project);
+    }
+    let mut output = POutput {
+        n_projects: projects.len(),
+        projects,
+    };
+
+    let mut score = compute_score_precomputed(&mut precomputed.clone(), &output, false)?;
+    // deltas are exact, recomputed only after a removal
+    while let Some((name, gain)) = leave_one_out(precomputed, &output, false)?
+        .into_iter()
+        .filter_map(|m| {
+            m.delta
+                .filter(|delta| *delta > 0)
+                .map(|delta| (m.name, delta))
+        })
+        .min_by_key(|(_, delta)| std::cmp::Reverse(*delta))
+    {
+        output = without(&output, &name);
+        score = (score as i64 + gain) as Score;
+        changes.push(Change::Harmful {
+            project: name,
+            gain: gain as Score,
+        });
+    }
+    Ok(Repair {
+        output,
+        score,
+        changes,
+    })
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::UnknownProject { project } => {
+                write!(f, "{}: unknown project, removed", project)
+            }
+            Change::DuplicateProject { project } => {
+                write!(f, "{}: planned more than once, removed", project)
+            }
+            Change::UnknownContributor {
+                project,
+                contributor,
+            } => write!(
+                f,
+                "{}: unknown contributor {} removed",
+                project, contributor
+            ),
+            Change::DuplicateContributor {
+                project,
+                contributor,
+            } => write!(
+                f,
+                "{}: duplicate contributor {} removed",
+                project, contributor
+            ),
+            Change::RoleCount {
+                project,
+                roles,
+                contributors,
+            } => write!(
+                f,
+                "{}: {} contributors for {} roles, completed or truncated",
+                project, contributors, roles
+            ),
+            Change::Restaffed(restaffed) => write!(f, "{}", restaffed),
+            Change::Dropped { project } => {
+                write!(f, "{}: cannot be staffed, removed", project)
+            }
+            Change::Harmful { project, gain } => {
+                write!(f, "{}: removed, score +{}", project, gain)
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use crate::data::POutput;
+use crate::explain::{late_projects, LateProject};
+use crate::score::{ProjectScore, Score};
+use serde::Serialize;
+
+#[derive(Serialize, Debug)]
+pub struct FileReport {
+    pub input: String,
+    pub output: String,
+    pub score: Score,
+    pub late_projects: Vec<LateProject>,
+}
+
+impl FileReport {
+    pub fn new(
+        input: &str,
+        output: &str,
+        output_data: &POutput,
+        project_scores: &[ProjectScore],
+    ) -> Self {
+        FileReport {
+            input: input.to_string(),
+            output: output.to_string(),
+            score: project_scores.iter().map(|p| p.score).sum(),
+            late_projects: late_projects(output_data, project_scores),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct Report {
+    pub files: Vec<FileReport>,
+    pub total_score: Score,
+}
+
+impl Report {
+    pub fn push(&mut self, file_report: FileReport) {
+        self.total_score += file_report.score;
+        self.files.push(file_report);
+    }
+}
@@ -2,53 +2,121 @@ use crate::data::{Id, PInput, POutput};
 use anyhow::bail;
 use fxhash::FxHashMap;
 use log::debug;
-use std::cmp::max;
 
 use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::io::{BufReader, Write};
+use std::io::{BufReader, Read, Write};
 use std::path::Path;
 
-pub(crate) type Score = usize;
-pub(crate) type Time = usize;
-pub(crate) type Level = usize;
-pub(crate) type LevelMap = FxHashMap<(Id, Id), Level>; // contributor id, skill id, level
+pub type Score = usize;
+pub type Time = usize;
+pub use crate::levels::{Level, LevelMap};
 
-#[derive(Serialize, Deserialize, Debug)]
-pub(crate) struct Contributor {
-    id: Id,
-    name: String,
-    skills: Vec<Id>,
-    next_availability: Time,
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Contributor {
+    pub id: Id,
+    pub name: String,
+    pub skills: Vec<Id>,
+    pub next_availability: Time,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub(crate) struct Project {
-    id: Id,
-    name: String,
-    skills: Vec<(Id, Level)>,
-    days_to_completion: usize,
-    score: usize,
-    best_before: usize,
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Project {
+    pub id: Id,
+    pub name: String,
+    pub skills: Vec<(Id, Level)>,
+    pub days_to_completion: usize,
+    pub score: usize,
+    pub best_before: usize,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub(crate) struct PreComputed {
-    contributors_id: FxHashMap<String, Id>,
-    projects_id: FxHashMap<String, Id>,
-    skills_id: FxHashMap<String, Id>,
-    levels: LevelMap,
-    contributors: Vec<Contributor>,
-    projects: Vec<Project>,
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PreComputed {
+    pub contributors_id: FxHashMap<String, Id>,
+    pub projects_id: FxHashMap<String, Id>,
+    pub skills_id: FxHashMap<String, Id>,
+    pub levels: LevelMap,
+    pub contributors: Vec<Contributor>,
+    pub projects: Vec<Project>,
 }
 
 #[derive(Debug)]
-pub(crate) struct PlannedProject {
+pub struct PlannedProject {
     id: Id,
     contributors: Vec<Id>,
 }
 
-pub(crate) fn precompute_from_input(input: &PInput) -> PreComputed {
+/// Contributor whose availability set the start time of a planned project.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Blocker {
+    pub role: usize, // index of the role (contributor) in the planned project
+    pub contributor: Id,
+    pub previous_planned_index: usize, // planned project they were on, in output file order
+}
+
+/// Outcome of a single planned project, in output file order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectScore {
+    pub id: Id,
+    pub start: Time,
+    pub end: Time,
+    pub best_before: Time,
+    pub score: Score,
+    pub max_score: Score,
+    pub blocker: Option<Blocker>,
+}
+
+impl ProjectScore {
+    pub fn days_late(&self) -> usize {
+        self.end.saturating_sub(self.best_before)
+    }
+
+    pub fn lost(&self) -> Score {
+        self.max_score - self.score
+    }
+}
+
+/// What happens during the simulation of an output, see `simulate`.
+///
+/// Events come in output file order, one planned project at a time: `ProjectStarted`,
+/// one `RoleFilled` per role, `ProjectEnded`, `ScoreAwarded`, then one `LevelUp` per
+/// contributor who learnt something. Projects overlap in time, use days to order
+/// events chronologically.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub enum Event {
+    ProjectStarted {
+        project: Id,
+        day: Time,
+        blocker: Option<Blocker>,
+    },
+    RoleFilled {
+        project: Id,
+        role: usize,
+        contributor: Id,
+        skill: Id,
+        required: Level,
+        level: Level,   // contributor level when the project starts
+        mentored: bool, // one level short, another contributor has the required level
+    },
+    ProjectEnded {
+        project: Id,
+        day: Time,
+    },
+    ScoreAwarded {
+        project: Id,
+        score: Score,
+        max_score: Score,
+        best_before: Time,
+    },
+    LevelUp {
+        contributor: Id,
+        skill: Id,
+        level: Level, // new level
+        day: Time,
+    },
+}
+
+pub fn precompute_from_input(input: &PInput) -> PreComputed {
     let mut projects_id: FxHashMap<String, Id> = FxHashMap::default();
     let mut projects: Vec<Project> = Vec::with_capacity(input.projects.len());
     let mut skills_id: FxHashMap<String, Id> = FxHashMap::default();
@@ -81,7 +149,7 @@ pub(crate) fn precompute_from_input(input: &PInput) -> PreComputed {
     }
     let mut contributors_id: FxHashMap<String, Id> = FxHashMap::default();
     let mut contributors = Vec::with_capacity(input.contributors.len());
-    let mut levels: FxHashMap<(Id, Id), Level> = FxHashMap::default();
+    let mut levels = LevelMap::default();
     for contributor in &input.contributors {
         contributors_id.insert(contributor.name.clone(), contributor.id);
         let mut skills: Vec<Id> = Vec::with_capacity(contributor.skills.len());
@@ -125,10 +193,35 @@ fn precompute_from_output(
     output: &POutput,
 ) -> anyhow::Result<Vec<PlannedProject>> {
     let mut planned_projects: Vec<PlannedProject> = Vec::with_capacity(output.projects.len());
+    let mut already_planned = vec![false; precomputed.projects.len()];
     for project in &output.projects {
+        let project_id = if let Some(project_id) = precomputed.projects_id.get(&*project.name) {
+            *project_id
+        } else {
+            bail!("unknown project {}", project.name);
+        };
+        if std::mem::replace(&mut already_planned[project_id], true) {
+            bail!("project {} is planned more than once", project.name);
+        }
+        let n_roles = precomputed.projects[project_id].skills.len();
+        if project.contributor_names.len() != n_roles {
+            bail!(
+                "project {} has {} roles but {} contributors are assigned",
+                project.name,
+                n_roles,
+                project.contributor_names.len()
+            );
+        }
         let mut contributors = Vec::with_capacity(project.contributor_names.len());
         for contributor_name in &project.contributor_names {
-            if let Some(contributor_id) = precomputed.contributors_id.get(&*contributor_name) {
+            if let Some(contributor_id) = precomputed.contributors_id.get(contributor_name) {
+                if contributors.contains(contributor_id) {
+                    bail!(
+                        "contributor {} is assigned more than once to project {}",
+                        contributor_name,
+                        project.name
+                    );
+                }
                 contributors.push(*contributor_id);
             } else {
                 bail!(
@@ -138,43 +231,45 @@ fn precompute_from_output(
                 );
             }
         }
-        if let Some(project_id) = precomputed.projects_id.get(&*project.name) {
-            planned_projects.push(PlannedProject {
-                id: *project_id,
-                contributors,
-            })
-        } else {
-            bail!("unknown project {}", project.name);
-        }
+        planned_projects.push(PlannedProject {
+            id: project_id,
+            contributors,
+        })
     }
     Ok(planned_projects)
 }
 
-fn check_contributors_level(
+/// Emits a `RoleFilled` per role, fails if a contributor is not skilled enough (unless `disable_checks`).
+fn fill_roles<F: FnMut(&Event)>(
     project: &Project,
     planned_project: &PlannedProject,
-    project_contributors: &[&Contributor],
     levels_map: &LevelMap,
+    disable_checks: bool,
+    on_event: &mut F,
 ) -> anyhow::Result<()> {
-    for ((skill_id, level_required), contributor_for_this_role_id) in
-        project.skills.iter().zip(&planned_project.contributors)
+    let level =
+        |contributor: Id, skill: Id| levels_map.get(&(contributor, skill)).cloned().unwrap_or(0);
+    for (role, ((skill_id, level_required), contributor_for_this_role_id)) in project
+        .skills
+        .iter()
+        .zip(&planned_project.contributors)
+        .enumerate()
     {
-        let max_level_among_contributors = project_contributors
-            .iter()
-            .map(|c| levels_map.get(&(c.id, *skill_id)).cloned().unwrap_or(0))
-            .max()
-            .unwrap_or(0);
-        let mentoring_available = max_level_among_contributors >= *level_required;
-        let level_required_with_mentoring: Level = if mentoring_available {
+        let contributor_level_for_this_role = level(*contributor_for_this_role_id, *skill_id);
+        let mentored = is_mentored(
+            contributor_level_for_this_role,
+            *level_required,
+            planned_project
+                .contributors
+                .iter()
+                .map(|c| level(*c, *skill_id)),
+        );
+        let level_required_with_mentoring: Level = if mentored {
             *level_required - 1
         } else {
             *level_required
         };
-        let contributor_level_for_this_role = levels_map
-            .get(&(*contributor_for_this_role_id, *skill_id))
-            .cloned()
-            .unwrap_or(0);
-        if level_required_with_mentoring > contributor_level_for_this_role {
+        if !disable_checks && level_required_with_mentoring > contributor_level_for_this_role {
             bail!(
                         "contributor {} level in {} is {} vs {} required for project {} ({}) (mentoring: {})",
                         contributor_for_this_role_id,
@@ -183,22 +278,48 @@ fn check_contributors_level(
                         level_required_with_mentoring,
                         project.id,
                         project.name,
-                        mentoring_available
+                        mentored
                     )
         }
+        on_event(&Event::RoleFilled {
+            project: project.id,
+            role,
+            contributor: *contributor_for_this_role_id,
+            skill: *skill_id,
+            required: *level_required,
+            level: contributor_level_for_this_role,
+            mentored,
+        });
     }
     Ok(())
 }
 
-fn project_score(project_start_time: Time, project: &Project) -> (Score, Time) {
-    let project_end_time: Time = project_start_time + project.days_to_completion;
-    let days_late: i64 = project_end_time as i64 - project.best_before as i64;
-    let score_increment = if days_late <= 0 {
-        project.score
+/// Whether a contributor at level `own` fills a role requiring `required` thanks to a mentor:
+/// mentoring only helps contributors one level short, `team_levels` are the levels of the
+/// whole team in the role skill.
+pub fn is_mentored(
+    own: Level,
+    required: Level,
+    mut team_levels: impl Iterator<Item = Level>,
+) -> bool {
+    own < required && own + 1 == required && team_levels.any(|level| level >= required)
+}
+
+/// Level of a contributor at level `own` after a project role requiring `required`,
+/// `None` if they do not level up.
+pub fn level_after(own: Level, required: Level) -> Option<Level> {
+    if own <= required {
+        Some(own.saturating_add(1))
     } else {
-        // late
-        max(0, project.score as i64 - days_late as i64) as usize // days late > 0
-    };
+        None
+    }
+}
+
+/// Score and end day of `project` started on `project_start_time`, one point lost per day late.
+pub fn project_score(project_start_time: Time, project: &Project) -> (Score, Time) {
+    let project_end_time: Time = project_start_time.saturating_add(project.days_to_completion);
+    let days_late = project_end_time.saturating_sub(project.best_before);
+    let score_increment = project.score.saturating_sub(days_late);
 
     debug!(
         "project {}: (start = {}, end = {}, late = {}, score = {})",
@@ -210,48 +331,85 @@ fn project_score(project_start_time: Time, project: &Project) -> (Score, Time) {
 fn update_next_availability(
     project_end_time: Time,
     planned_project: &PlannedProject,
+    planned_project_index: usize,
     contributors: &mut [Contributor],
+    last_projects: &mut [Option<usize>],
 ) {
     for contributor_id in &planned_project.contributors {
         if let Some(c) = contributors.get_mut(*contributor_id) {
             c.next_availability = project_end_time;
+            last_projects[*contributor_id] = Some(planned_project_index);
         }
     }
 }
 
-fn update_level(project: &Project, planned_project: &PlannedProject, levels_map: &mut LevelMap) {
+fn find_blocker(
+    project_contributors: &[&Contributor],
+    last_projects: &[Option<usize>],
+) -> Option<Blocker> {
+    let (role, contributor) = project_contributors
+        .iter()
+        .enumerate()
+        .rev() // max_by_key returns the last max, prefer the first role on ties
+        .max_by_key(|(_, c)| c.next_availability)?;
+    let previous_planned_index = last_projects[contributor.id]?;
+    Some(Blocker {
+        role,
+        contributor: contributor.id,
+        previous_planned_index,
+    })
+}
+
+fn update_level<F: FnMut(&Event)>(
+    project: &Project,
+    planned_project: &PlannedProject,
+    levels_map: &mut LevelMap,
+    day: Time,
+    on_event: &mut F,
+) {
     for ((skill_id, level_required), contributor_for_this_role_id) in
         project.skills.iter().zip(&planned_project.contributors)
     {
-        if let Some(contributor_level) =
-            levels_map.get_mut(&(*contributor_for_this_role_id, *skill_id))
-        {
-            if *contributor_level <= *level_required {
-                *contributor_level += 1;
-                debug!(
-                    "contributor {} reached level {} in {}",
-                    contributor_for_this_role_id, contributor_level, skill_id
-                );
-            }
-        } else {
-            levels_map.insert((*contributor_for_this_role_id, *skill_id), 1);
-            debug!(
-                "contributor {} reached level {} in {}",
-                contributor_for_this_role_id, 1, skill_id
-            );
-        }
+        let key = (*contributor_for_this_role_id, *skill_id);
+        let own = levels_map.get(&key).cloned().unwrap_or(0);
+        let level = match level_after(own, *level_required) {
+            Some(level) => level,
+            None => continue,
+        };
+        levels_map.insert(key, level);
+        debug!(
+            "contributor {} reached level {} in {}",
+            contributor_for_this_role_id, level, skill_id
+        );
+        on_event(&Event::LevelUp {
+            contributor: *contributor_for_this_role_id,
+            skill: *skill_id,
+            level,
+            day,
+        });
     }
 }
 
-pub(crate) fn decode_precomputed(bin_path: &Path) -> anyhow::Result<PreComputed> {
+/// Header of `.bin` cache files, the last byte is bumped whenever `PreComputed` changes.
+pub const CACHE_HEADER: &[u8; 8] = b"HC22BIN\x02";
+
+pub fn decode_precomputed(bin_path: &Path) -> anyhow::Result<PreComputed> {
     let file = File::open(bin_path)?;
-    let reader = BufReader::new(file);
+    let mut reader = BufReader::new(file);
+    let mut header = [0; CACHE_HEADER.len()];
+    if reader.read_exact(&mut header).is_err() || &header != CACHE_HEADER {
+        bail!(
+            "{} is not a cache file of this version, regenerate it with --cache",
+            bin_path.display()
+        );
+    }
     let decoded: PreComputed = bincode::deserialize_from(reader)?;
     Ok(decoded)
 }
 
-pub(crate) fn encode_precomputed(precomputed: &PreComputed, bin_path: &Path) -> anyhow::Result<()> {
-    let encoded: Vec<u8> = bincode::serialize(&precomputed)?;
+pub fn encode_precomputed(precomputed: &PreComputed, bin_path: &Path) -> anyhow::Result<()> {
+    let mut encoded: Vec<u8> = CACHE_HEADER.to_vec();
+    bincode::serialize_into(&mut encoded, &precomputed)?;
     let mut output = File::create(bin_path)?;
     output.write_all(encoded.as_slice())?;
     Ok(())
@@ -265,22 +423,83 @@ fn compute_score(input: &PInput, output: &POutput, disable_checks: bool) -> anyh
     compute_score_precomputed(&mut precomputed, output, disable_checks)
 }
 
-pub(crate) fn compute_score_precomputed(
+pub fn compute_score_precomputed(
     precomputed: &mut PreComputed,
     output: &POutput,
     disable_checks: bool,
 ) -> anyhow::Result<Score> {
+    let project_scores = compute_project_scores_precomputed(precomputed, output, disable_checks)?;
+    Ok(project_scores.iter().map(|p| p.score).sum())
+}
+
+pub fn compute_project_scores_precomputed(
+    precomputed: &mut PreComputed,
+    output: &POutput,
+    disable_checks: bool,
+) -> anyhow::Result<Vec<ProjectScore>> {
+    let mut project_scores: Vec<ProjectScore> = Vec::with_capacity(output.projects.len());
+    simulate(precomputed, output, disable_checks, |event| match event {
+        Event::ProjectStarted {
+            project,
+            day,
+            blocker,
+        } => project_scores.push(ProjectScore {
+            id: *project,
+            start: *day,
+            end: *day,
+            best_before: 0,
+            score: 0,
+            max_score: 0,
+            blocker: blocker.clone(),
+        }),
+        Event::ProjectEnded { day, .. } => {
+            if let Some(project_score) = project_scores.last_mut() {
+                project_score.end = *day;
+            }
+        }
+        Event::ScoreAwarded {
+            score,
+            max_score,
+            best_before,
+            ..
+        } => {
+            if let Some(project_score) = project_scores.last_mut() {
+                project_score.score = *score;
+                project_score.max_score = *max_score;
+                project_score.best_before = *best_before;
+            }
+        }
+        _ => {}
+    })?;
+    Ok(project_scores)
+}
+
+/// Simulates `output` project by project and reports every step to `on_event`.
+///
+/// This is the only implementation of the rules, scores, reports and explanations are
+/// all built from these events. Stops at the first invalid planned project (events
+/// already reported for it are not rolled back). Like the other scoring functions it
+/// updates contributors availability and levels in `precomputed`.
+pub fn simulate<F: FnMut(&Event)>(
+    precomputed: &mut PreComputed,
+    output: &POutput,
+    disable_checks: bool,
+    mut on_event: F,
+) -> anyhow::Result<()> {
     let planned_projects = precompute_from_output(precomputed, output)?;
-    let mut contributors = &mut precomputed.contributors;
+    let contributors = &mut precomputed.contributors;
     let projects = &precomputed.projects;
 
     debug!("{:?}", contributors);
     debug!("{:?}", projects);
     debug!("{:?}", planned_projects);
 
-    let mut score: Score = 0;
+    // last planned project each contributor worked on
+    let mut last_projects: Vec<Option<usize>> = vec![None; contributors.len()];
+    // so that summing project scores never overflows
+    let mut total_score: Score = 0;
 
-    for planned_project in &planned_projects {
+    for (planned_project_index, planned_project) in planned_projects.iter().enumerate() {
         if let Some(project) = projects.get(planned_project.id) {
             let mut project_contributors: Vec<&Contributor> =
                 Vec::with_capacity(planned_project.contributors.len());
@@ -295,33 +514,60 @@ pub(crate) fn compute_score_precomputed(
                 project, project_contributors
             );
 
-            if !disable_checks {
-                check_contributors_level(
-                    project,
-                    planned_project,
-                    &project_contributors,
-                    &precomputed.levels,
-                )?;
-            }
-
             if let Some(project_start_time) = project_contributors
                 .iter()
                 .map(|c| c.next_availability)
                 .max()
             {
+                on_event(&Event::ProjectStarted {
+                    project: project.id,
+                    day: project_start_time,
+                    blocker: find_blocker(&project_contributors, &last_projects),
+                });
+                fill_roles(
+                    project,
+                    planned_project,
+                    &precomputed.levels,
+                    disable_checks,
+                    &mut on_event,
+                )?;
                 let (score_increment, project_end_time) =
                     project_score(project_start_time, project);
-                score += score_increment;
-                update_next_availability(project_end_time, planned_project, &mut contributors);
+                total_score = match total_score.checked_add(score_increment) {
+                    Some(total_score) => total_score,
+                    None => bail!("total score overflows"),
+                };
+                on_event(&Event::ProjectEnded {
+                    project: project.id,
+                    day: project_end_time,
+                });
+                on_event(&Event::ScoreAwarded {
+                    project: project.id,
+                    score: score_increment,
+                    max_score: project.score,
+                    best_before: project.best_before,
+                });
+                update_next_availability(
+                    project_end_time,
+                    planned_project,
+                    planned_project_index,
+                    contributors,
+                    &mut last_projects,
+                );
+                // update contributors level
+                update_level(
+                    project,
+                    planned_project,
+                    &mut precomputed.levels,
+                    project_end_time,
+                    &mut on_event,
+                );
             } else {
                 bail!("could not compute project start time");
             }
-
-            // update contributors level
-            update_level(project, planned_project, &mut precomputed.levels);
         } else {
             bail!("unknown project {}", planned_project.id);
         }
     }
-    Ok(score)
+    Ok(())
 }
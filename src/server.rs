@@ -0,0 +1,206 @@
+use crate::leaderboard::Leaderboard;
+use crate::parser::parse_output;
+use crate::report::FileReport;
+use crate::score::{compute_project_scores_precomputed, PreComputed};
+use fxhash::FxHashMap;
+use log::{info, warn};
+use serde::Serialize;
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+pub struct Dataset {
+    pub input_file_path: String,
+    pub precomputed: PreComputed, // pristine, cloned for each submission
+}
+
+pub type Datasets = FxHashMap<String, Dataset>;
+
+/// Largest accepted output file, the biggest reference output is a few MB.
+pub const MAX_BODY_SIZE: usize = 64 * 1024 * 1024;
+
+#[derive(Serialize)]
+struct ErrorReport {
+    error: String,
+}
+
+fn json_response<T: Serialize>(value: &T, status_code: u16) -> Response<std::io::Cursor<Vec<u8>>> {
+    let body = serde_json::to_string_pretty(value).expect("report is serializable");
+    let content_type =
+        Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).expect("valid header");
+    Response::from_string(body)
+        .with_status_code(status_code)
+        .with_header(content_type)
+}
+
+fn error_response(error: String, status_code: u16) -> Response<std::io::Cursor<Vec<u8>>> {
+    json_response(&ErrorReport { error }, status_code)
+}
+
+fn score_submission(
+    dataset: &Dataset,
+    url: &str,
+    output_content: &str,
+) -> anyhow::Result<FileReport> {
+    let output_data = parse_output(output_content)?;
+    let mut precomputed = dataset.precomputed.clone();
+    let project_scores = compute_project_scores_precomputed(&mut precomputed, &output_data, false)?;
+    Ok(FileReport::new(
+        &dataset.input_file_path,
+        url,
+        &output_data,
+        &project_scores,
+    ))
+}
+
+struct State {
+    datasets: Datasets,
+    leaderboard: Mutex<Leaderboard>,
+}
+
+/// Splits `/path?key=value&...` into the path and the value of `key`.
+fn split_url<'a>(url: &'a str, key: &str) -> (&'a str, Option<&'a str>) {
+    match url.split_once('?') {
+        Some((path, query)) => (
+            path,
+            query
+                .split('&')
+                .filter_map(|kv| kv.split_once('='))
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v),
+        ),
+        None => (url, None),
+    }
+}
+
+fn html_response(body: String) -> Response<std::io::Cursor<Vec<u8>>> {
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"text/html; charset=utf-8"[..])
+        .expect("valid header");
+    Response::from_string(body).with_header(content_type)
+}
+
+/// Request body as text, or the error response: 413 if larger than `limit`, 400 if unreadable.
+fn read_body(
+    request: &mut Request,
+    limit: usize,
+) -> Result<String, Response<std::io::Cursor<Vec<u8>>>> {
+    let too_large = || error_response(format!("request body larger than {} bytes", limit), 413);
+    if request.body_length().is_some_and(|length| length > limit) {
+        return Err(too_large());
+    }
+    let mut body = vec![];
+    if let Err(e) = request
+        .as_reader()
+        .take(limit as u64 + 1)
+        .read_to_end(&mut body)
+    {
+        return Err(error_response(
+            format!("cannot read request body: {}", e),
+            400,
+        ));
+    }
+    if body.len() > limit {
+        return Err(too_large());
+    }
+    String::from_utf8(body)
+        .map_err(|_| error_response(String::from("request body is not valid UTF-8"), 400))
+}
+
+/// Scores a submission, errors are reported to the client as JSON with their status code.
+fn score(
+    state: &State,
+    request: &mut Request,
+    dataset_name: &str,
+    user: Option<&str>,
+) -> Response<std::io::Cursor<Vec<u8>>> {
+    let dataset = match state.datasets.get(dataset_name) {
+        Some(dataset) => dataset,
+        None => return error_response(format!("unknown dataset {}", dataset_name), 404),
+    };
+    let url = request.url().to_string();
+    let output_content = match read_body(request, MAX_BODY_SIZE) {
+        Ok(output_content) => output_content,
+        Err(response) => return response,
+    };
+    let report = match score_submission(dataset, &url, &output_content) {
+        Ok(report) => report,
+        Err(e) => return error_response(format!("{:#}", e), 400),
+    };
+    info!("{} score: {}", url, report.score);
+    if let Some(user) = user {
+        let mut leaderboard = state.leaderboard.lock().expect("leaderboard lock poisoned");
+        if let Err(e) = leaderboard.submit(user, dataset_name, &output_content, report.score) {
+            return error_response(format!("{:#}", e), 400);
+        }
+    }
+    json_response(&report, 200)
+}
+
+fn handle(state: &State, mut request: Request) -> anyhow::Result<()> {
+    let url = request.url().to_string();
+    let (path, user) = split_url(&url, "user");
+    let response = match (request.method(), path) {
+        (Method::Post, path) if path.starts_with("/score/") => {
+            score(state, &mut request, &path["/score/".len()..], user)
+        }
+        (_, path) if path.starts_with("/score/") => error_response(String::from("use POST"), 405),
+        (Method::Get, "/leaderboard") => {
+            let leaderboard = state.leaderboard.lock().expect("leaderboard lock poisoned");
+            json_response(&leaderboard.standings(), 200)
+        }
+        (Method::Get, "/leaderboard/history") => {
+            let leaderboard = state.leaderboard.lock().expect("leaderboard lock poisoned");
+            json_response(&leaderboard.history(user), 200)
+        }
+        (Method::Get, "/") => {
+            let mut datasets: Vec<String> = state.datasets.keys().cloned().collect();
+            datasets.sort();
+            let leaderboard = state.leaderboard.lock().expect("leaderboard lock poisoned");
+            html_response(leaderboard.to_html(&datasets))
+        }
+        _ => error_response(format!("not found {}", url), 404),
+    };
+    request.respond(response)?;
+    Ok(())
+}
+
+/// Serves until the process is killed:
+///
+/// * `POST /score/{dataset}[?user=<name>]`: the request body is an output file,
+///   the response is the JSON score report. Valid submissions with a user are
+///   added to the leaderboard. Bodies over `MAX_BODY_SIZE` are rejected.
+/// * `GET /leaderboard`: best score per dataset and total for each user (JSON).
+/// * `GET /leaderboard/history[?user=<name>]`: every submission (JSON).
+/// * `GET /`: leaderboard page.
+pub fn serve(
+    datasets: Datasets,
+    leaderboard: Leaderboard,
+    port: u16,
+    workers: usize,
+) -> anyhow::Result<()> {
+    let server = Arc::new(Server::http(("127.0.0.1", port)).map_err(|e| anyhow::anyhow!(e))?);
+    let state = Arc::new(State {
+        datasets,
+        leaderboard: Mutex::new(leaderboard),
+    });
+    // the actual port when 0 was asked for
+    println!("listening on http://{}", server.server_addr());
+    let handles: Vec<_> = (0..workers)
+        .map(|_| {
+            let server = Arc::clone(&server);
+            let state = Arc::clone(&state);
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    if let Err(e) = handle(&state, request) {
+                        warn!("{:#}", e);
+                    }
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().expect("worker panicked");
+    }
+    Ok(())
+}
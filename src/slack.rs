@@ -0,0 +1,142 @@
+use crate::data::POutput;
+use crate::score::{ProjectScore, Score, Time};
+use serde::Serialize;
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Earlier planned project a contributor was busy on until a project started.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Link {
+    pub previous_planned_index: usize, // index of the planned project, in output file order
+    pub contributor: String,
+}
+
+/// How much a planned project can be delayed, and what determines its start date.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ProjectSlack {
+    pub name: String,
+    pub start: Time,
+    pub end: Time,
+    pub best_before: Time,
+    /// `best_before - end`: days the project can be delayed without losing points, negative when late
+    pub slack: i64,
+    pub lost: Score,
+    /// `None` when every contributor is available from day 0
+    pub blocked_by: Option<Link>,
+}
+
+fn days(from: Time, to: Time) -> i64 {
+    if to >= from {
+        i64::try_from(to - from).unwrap_or(i64::MAX)
+    } else {
+        -i64::try_from(from - to).unwrap_or(i64::MAX)
+    }
+}
+
+/// Slack of every planned project, in output file order.
+///
+/// `project_scores` must come from simulating `output`.
+pub fn project_slacks(output: &POutput, project_scores: &[ProjectScore]) -> Vec<ProjectSlack> {
+    project_scores
+        .iter()
+        .zip(&output.projects)
+        .map(|(project_score, planned_project)| ProjectSlack {
+            name: planned_project.name.clone(),
+            start: project_score.start,
+            end: project_score.end,
+            best_before: project_score.best_before,
+            slack: days(project_score.end, project_score.best_before),
+            lost: project_score.lost(),
+            blocked_by: project_score.blocker.as_ref().map(|blocker| Link {
+                previous_planned_index: blocker.previous_planned_index,
+                contributor: planned_project.contributor_names[blocker.role].clone(),
+            }),
+        })
+        .collect()
+}
+
+/// Planned projects that determine the start date of project `index`, first one first and
+/// `index` last: each project starts when the previous one ends.
+pub fn critical_chain(slacks: &[ProjectSlack], index: usize) -> Vec<usize> {
+    let mut chain = vec![index];
+    let mut current = index;
+    while let Some(link) = &slacks[current].blocked_by {
+        current = link.previous_planned_index;
+        chain.push(current);
+    }
+    chain.reverse();
+    chain
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Step {
+    pub project: String,
+    pub start: Time,
+    pub end: Time,
+    pub waits_for: Option<String>, // contributor busy on the previous step
+}
+
+/// Chain of projects ending with a late project.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CriticalChain {
+    pub project: String,
+    pub days_late: usize,
+    pub lost: Score,
+    pub steps: Vec<Step>,
+}
+
+/// Critical chain of every late project, most points lost first.
+pub fn critical_chains(slacks: &[ProjectSlack]) -> Vec<CriticalChain> {
+    let mut chains: Vec<CriticalChain> = slacks
+        .iter()
+        .enumerate()
+        .filter(|(_, slack)| slack.slack < 0)
+        .map(|(index, slack)| CriticalChain {
+            project: slack.name.clone(),
+            days_late: slack.end.saturating_sub(slack.best_before),
+            lost: slack.lost,
+            steps: critical_chain(slacks, index)
+                .into_iter()
+                .map(|i| Step {
+                    project: slacks[i].name.clone(),
+                    start: slacks[i].start,
+                    end: slacks[i].end,
+                    waits_for: slacks[i].blocked_by.as_ref().map(|l| l.contributor.clone()),
+                })
+                .collect(),
+        })
+        .collect();
+    chains.sort_by_key(|chain| std::cmp::Reverse(chain.lost));
+    chains
+}
+
+impl fmt::Display for ProjectSlack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: day {} to {}, best before {}, slack {}",
+            self.name, self.start, self.end, self.best_before, self.slack
+        )
+    }
+}
+
+impl fmt::Display for CriticalChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} days late, {} points lost",
+            self.project, self.days_late, self.lost
+        )?;
+        for step in &self.steps {
+            write!(
+                f,
+                "\n  day {} to {}: {}",
+                step.start, step.end, step.project
+            )?;
+            if let Some(contributor) = &step.waits_for {
+                write!(f, " (waits for {})", contributor)?;
+            }
+        }
+        Ok(())
+    }
+}
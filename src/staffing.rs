@@ -0,0 +1,149 @@
+use crate::data::Id;
+use crate::score::{Level, PreComputed, Time};
+use anyhow::bail;
+use serde::Serialize;
+
+/// Contributor able to fill a role.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub contributor: Id,
+    pub level: Level,
+    pub next_availability: Time,
+    /// one level short, another contributor on the project must have the required level
+    pub needs_mentor: bool,
+}
+
+/// Contributors by skill, sorted by level, to find who can fill a role without scanning
+/// every contributor.
+///
+/// Built from the levels of a `PreComputed`, e.g. after `state::replay_until`, call
+/// `set_level` to keep it in sync when a contributor levels up.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StaffingIndex {
+    by_skill: Vec<Vec<(Level, Id)>>, // skill id -> (level, contributor id), highest level first
+}
+
+impl StaffingIndex {
+    pub fn new(precomputed: &PreComputed) -> Self {
+        let mut index = StaffingIndex {
+            by_skill: vec![vec![]; precomputed.skills_id.len()],
+        };
+        for ((contributor, skill), level) in precomputed.levels.iter() {
+            if *level > 0 {
+                index.skill_mut(skill).push((*level, contributor));
+            }
+        }
+        for contributors in &mut index.by_skill {
+            contributors.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        }
+        index
+    }
+
+    fn skill_mut(&mut self, skill: Id) -> &mut Vec<(Level, Id)> {
+        if skill >= self.by_skill.len() {
+            self.by_skill.resize_with(skill + 1, Vec::new);
+        }
+        &mut self.by_skill[skill]
+    }
+
+    fn position(contributors: &[(Level, Id)], entry: (Level, Id)) -> Result<usize, usize> {
+        contributors.binary_search_by(|e| entry.0.cmp(&e.0).then(e.1.cmp(&entry.1)))
+    }
+
+    /// Moves `contributor` from level `previous` to `level` in `skill`, 0 meaning no level.
+    pub fn set_level(&mut self, contributor: Id, skill: Id, previous: Level, level: Level) {
+        let contributors = self.skill_mut(skill);
+        if previous > 0 {
+            if let Ok(position) = Self::position(contributors, (previous, contributor)) {
+                contributors.remove(position);
+            }
+        }
+        if level > 0 {
+            if let Err(position) = Self::position(contributors, (level, contributor)) {
+                contributors.insert(position, (level, contributor));
+            }
+        }
+    }
+
+    /// (level, contributor id) of contributors with at least `level` in `skill`, highest level first.
+    pub fn at_least(&self, skill: Id, level: Level) -> &[(Level, Id)] {
+        match self.by_skill.get(skill) {
+            Some(contributors) => {
+                &contributors[..contributors.partition_point(|(l, _)| *l >= level)]
+            }
+            None => &[],
+        }
+    }
+
+    /// (level, contributor id) of contributors with exactly `level` in `skill`, `level` > 0.
+    fn exactly(&self, skill: Id, level: Level) -> &[(Level, Id)] {
+        let at_least = self.at_least(skill, level);
+        &at_least[at_least.partition_point(|(l, _)| *l > level)..]
+    }
+
+    /// Contributors free on `day` who can fill role `role` of project `project`, on their own
+    /// or mentored, sorted by: no mentor needed first, highest level, earliest availability.
+    ///
+    /// Pass `Time::MAX` to ignore availability.
+    pub fn candidates(
+        &self,
+        precomputed: &PreComputed,
+        project: Id,
+        role: usize,
+        day: Time,
+    ) -> anyhow::Result<Vec<Candidate>> {
+        let (skill, required) = match precomputed
+            .projects
+            .get(project)
+            .and_then(|p| p.skills.get(role))
+        {
+            Some(role) => *role,
+            None => bail!("project {} has no role {}", project, role),
+        };
+        let candidate = |level: Level, contributor: Id| {
+            let next_availability = precomputed.contributors[contributor].next_availability;
+            if next_availability <= day {
+                Some(Candidate {
+                    contributor,
+                    level,
+                    next_availability,
+                    needs_mentor: level < required,
+                })
+            } else {
+                None
+            }
+        };
+        let mut candidates: Vec<Candidate> = self
+            .at_least(skill, required)
+            .iter()
+            .filter_map(|(level, contributor)| candidate(*level, *contributor))
+            .collect();
+        match required {
+            0 | 1 => {
+                // anyone without the skill fills a level 0 role, or a level 1 role with a mentor
+                let mut has_skill = vec![false; precomputed.contributors.len()];
+                for (_, contributor) in self.at_least(skill, 1) {
+                    has_skill[*contributor] = true;
+                }
+                candidates.extend(
+                    (0..precomputed.contributors.len())
+                        .filter(|c| !has_skill[*c])
+                        .filter_map(|c| candidate(0, c)),
+                );
+            }
+            _ => candidates.extend(
+                self.exactly(skill, required - 1)
+                    .iter()
+                    .filter_map(|(level, contributor)| candidate(*level, *contributor)),
+            ),
+        }
+        candidates.sort_by(|a, b| {
+            a.needs_mentor
+                .cmp(&b.needs_mentor)
+                .then(b.level.cmp(&a.level))
+                .then(a.next_availability.cmp(&b.next_availability))
+                .then(a.contributor.cmp(&b.contributor))
+        });
+        Ok(candidates)
+    }
+}
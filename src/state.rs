@@ -0,0 +1,122 @@
+use crate::data::{Id, POutput};
+use crate::score::{simulate, Event, Level, PreComputed, Time};
+use anyhow::bail;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Point of the simulation to stop at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Until {
+    /// everything started or completed on or before this day
+    Day(Time),
+    /// the first `n` planned projects, in output file order
+    Projects(usize),
+}
+
+/// Replays `output` until `until`, returns contributors availability and levels at that point.
+///
+/// `precomputed` is left untouched. With `Until::Day`, contributors on a project that started
+/// on or before that day are available when it ends, levels only include projects completed
+/// on or before that day.
+pub fn replay_until(
+    precomputed: &PreComputed,
+    output: &POutput,
+    disable_checks: bool,
+    until: Until,
+) -> anyhow::Result<PreComputed> {
+    match until {
+        Until::Projects(n) => {
+            if n > output.projects.len() {
+                bail!(
+                    "only {} projects are planned, cannot replay {}",
+                    output.projects.len(),
+                    n
+                );
+            }
+            let prefix = POutput {
+                n_projects: n,
+                projects: output.projects[..n].to_vec(),
+            };
+            let mut state = precomputed.clone();
+            simulate(&mut state, &prefix, disable_checks, |_| {})?;
+            Ok(state)
+        }
+        Until::Day(day) => {
+            let mut state = precomputed.clone();
+            let mut started = false; // current planned project started on or before `day`
+            let mut team: Vec<Id> = vec![];
+            simulate(
+                &mut precomputed.clone(),
+                output,
+                disable_checks,
+                |event| match event {
+                    Event::ProjectStarted { day: start, .. } => {
+                        started = *start <= day;
+                        team.clear();
+                    }
+                    Event::RoleFilled { contributor, .. } => team.push(*contributor),
+                    Event::ProjectEnded { day: end, .. } if started => {
+                        for contributor in &team {
+                            state.contributors[*contributor].next_availability = *end;
+                        }
+                    }
+                    Event::LevelUp {
+                        contributor,
+                        skill,
+                        level,
+                        day: end,
+                    } if *end <= day => {
+                        state.levels.insert((*contributor, *skill), *level);
+                    }
+                    _ => {}
+                },
+            )?;
+            Ok(state)
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ContributorState {
+    pub name: String,
+    pub next_availability: Time,
+    pub levels: BTreeMap<String, Level>, // skill name -> level, only skills required by projects
+}
+
+/// Contributors state by name, in input file order.
+pub fn contributors_state(state: &PreComputed) -> Vec<ContributorState> {
+    let mut skill_names: Vec<&str> = vec![""; state.skills_id.len()];
+    for (name, id) in &state.skills_id {
+        skill_names[*id] = name;
+    }
+    let mut contributors: Vec<ContributorState> = state
+        .contributors
+        .iter()
+        .map(|c| ContributorState {
+            name: c.name.clone(),
+            next_availability: c.next_availability,
+            levels: BTreeMap::new(),
+        })
+        .collect();
+    for ((contributor, skill), level) in state.levels.iter() {
+        if let Some(c) = contributors.get_mut(contributor) {
+            c.levels.insert(skill_names[skill].to_string(), *level);
+        }
+    }
+    contributors
+}
+
+impl fmt::Display for ContributorState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} available on day {}:",
+            self.name, self.next_availability
+        )?;
+        for (skill, level) in &self.levels {
+            write!(f, " {} {}", skill, level)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+use crate::data::{Id, MAX_PREALLOCATION};
+use crate::score::{Contributor, LevelMap, PreComputed, Project};
+use anyhow::{bail, Context};
+use fxhash::FxHashMap;
+use log::debug;
+use std::io::BufRead;
+
+/// Line reader reusing a single buffer, keeps track of the line number for error messages.
+struct Lines<R: BufRead> {
+    reader: R,
+    buffer: String,
+    line_number: usize,
+}
+
+impl<R: BufRead> Lines<R> {
+    fn new(reader: R) -> Self {
+        Lines {
+            reader,
+            buffer: String::new(),
+            line_number: 0,
+        }
+    }
+
+    /// Next line (without its `\n` or `\r\n` end of line, which is required) and its number.
+    fn next_line(&mut self) -> anyhow::Result<(usize, &str)> {
+        self.buffer.clear();
+        self.line_number += 1;
+        if self.reader.read_line(&mut self.buffer)? == 0 {
+            bail!("line {}: unexpected end of input", self.line_number);
+        }
+        match self.buffer.strip_suffix('\n') {
+            Some(line) => Ok((self.line_number, line.strip_suffix('\r').unwrap_or(line))),
+            None => bail!("line {}: missing end of line", self.line_number),
+        }
+    }
+}
+
+/// Fields of a line, separated by a single space as in `parser`.
+fn split_fields(line: &str) -> std::str::Split<'_, char> {
+    line.split(' ')
+}
+
+fn next_field<'a>(
+    fields: &mut impl Iterator<Item = &'a str>,
+    line_number: usize,
+    what: &str,
+) -> anyhow::Result<&'a str> {
+    match fields.next() {
+        Some(field) if !field.is_empty() => Ok(field),
+        _ => bail!("line {}: missing {}", line_number, what),
+    }
+}
+
+fn next_number<'a>(
+    fields: &mut impl Iterator<Item = &'a str>,
+    line_number: usize,
+    what: &str,
+) -> anyhow::Result<usize> {
+    let field = next_field(fields, line_number, what)?;
+    if !field.bytes().all(|b| b.is_ascii_digit()) {
+        bail!("line {}: invalid {} {:?}", line_number, what, field);
+    }
+    field
+        .parse()
+        .with_context(|| format!("line {}: invalid {} {:?}", line_number, what, field))
+}
+
+fn end_of_line<'a>(
+    fields: &mut impl Iterator<Item = &'a str>,
+    line_number: usize,
+) -> anyhow::Result<()> {
+    match fields.next() {
+        Some(field) => bail!("line {}: unexpected field {:?}", line_number, field),
+        None => Ok(()),
+    }
+}
+
+/// Interns `name`, allocating a new skill id on first use.
+fn skill_id(skills_id: &mut FxHashMap<String, Id>, name: &str) -> Id {
+    if let Some(id) = skills_id.get(name) {
+        *id
+    } else {
+        let id = skills_id.len();
+        skills_id.insert(name.to_string(), id);
+        id
+    }
+}
+
+/// Builds `PreComputed` directly from an input file, without materializing `PInput`.
+///
+/// Accepts the same inputs as `parser::parse_input`. Skill names are interned while
+/// reading, only skills required by at least one project are kept in contributors
+/// skills and levels (same as `precompute_from_input`).
+///
+/// Skill ids are given in order of first appearance in the file, contributors skills
+/// included, while `precompute_from_input` numbers the skills required by projects
+/// first: the same skill may have a different id, scores do not depend on it.
+pub fn precompute_from_reader<R: BufRead>(reader: R) -> anyhow::Result<PreComputed> {
+    let mut lines = Lines::new(reader);
+
+    let (_, line) = lines.next_line()?;
+    let mut fields = split_fields(line);
+    let n_contributors = next_number(&mut fields, 1, "number of contributors")?;
+    let n_projects = next_number(&mut fields, 1, "number of projects")?;
+    end_of_line(&mut fields, 1)?;
+
+    let mut skills_id: FxHashMap<String, Id> = FxHashMap::default();
+    let mut levels = LevelMap::default();
+    let mut contributors_id: FxHashMap<String, Id> = FxHashMap::default();
+    let mut contributors = Vec::with_capacity(n_contributors.min(MAX_PREALLOCATION));
+    for id in 0..n_contributors {
+        let (line_number, line) = lines.next_line()?;
+        let mut fields = split_fields(line);
+        let name = next_field(&mut fields, line_number, "contributor name")?.to_string();
+        let n_skills = next_number(&mut fields, line_number, "number of skills")?;
+        end_of_line(&mut fields, line_number)?;
+        let mut skills = Vec::with_capacity(n_skills.min(MAX_PREALLOCATION));
+        for _ in 0..n_skills {
+            let (line_number, line) = lines.next_line()?;
+            let mut fields = split_fields(line);
+            let skill_name = next_field(&mut fields, line_number, "skill name")?;
+            let level = next_number(&mut fields, line_number, "skill level")?;
+            end_of_line(&mut fields, line_number)?;
+            let skill = skill_id(&mut skills_id, skill_name);
+            skills.push(skill);
+            levels.insert((id, skill), level);
+        }
+        contributors_id.insert(name.clone(), id);
+        contributors.push(Contributor {
+            id,
+            name,
+            skills,
+            next_availability: 0, // ready to work on project at t = 0
+        });
+    }
+
+    let mut projects_id: FxHashMap<String, Id> = FxHashMap::default();
+    let mut projects = Vec::with_capacity(n_projects.min(MAX_PREALLOCATION));
+    for id in 0..n_projects {
+        let (line_number, line) = lines.next_line()?;
+        let mut fields = split_fields(line);
+        let name = next_field(&mut fields, line_number, "project name")?.to_string();
+        let days_to_completion = next_number(&mut fields, line_number, "days to completion")?;
+        let score = next_number(&mut fields, line_number, "score")?;
+        let best_before = next_number(&mut fields, line_number, "best before")?;
+        let n_roles = next_number(&mut fields, line_number, "number of roles")?;
+        end_of_line(&mut fields, line_number)?;
+        let mut skills = Vec::with_capacity(n_roles.min(MAX_PREALLOCATION));
+        for _ in 0..n_roles {
+            let (line_number, line) = lines.next_line()?;
+            let mut fields = split_fields(line);
+            let skill_name = next_field(&mut fields, line_number, "skill name")?;
+            let level = next_number(&mut fields, line_number, "skill level")?;
+            end_of_line(&mut fields, line_number)?;
+            skills.push((skill_id(&mut skills_id, skill_name), level));
+        }
+        projects_id.insert(name.clone(), id);
+        projects.push(Project {
+            id,
+            name,
+            skills,
+            days_to_completion,
+            score,
+            best_before,
+        });
+    }
+
+    // drop skills no project requires
+    let mut required = vec![false; skills_id.len()];
+    for project in &projects {
+        for (skill, _) in &project.skills {
+            required[*skill] = true;
+        }
+    }
+    for contributor in contributors.iter_mut() {
+        contributor.skills.retain(|skill| required[*skill]);
+    }
+    levels.retain(|(_, skill), _| required[*skill]);
+    debug!(
+        "{} skills, {} required by projects",
+        skills_id.len(),
+        required.iter().filter(|r| **r).count()
+    );
+
+    Ok(PreComputed {
+        contributors_id,
+        projects_id,
+        skills_id,
+        levels,
+        contributors,
+        projects,
+    })
+}
@@ -0,0 +1,116 @@
+use crate::history::{dataset_name, try_track, History};
+use crate::load::load_output_and_content;
+use crate::score::{compute_score_precomputed, PreComputed, Score};
+use log::{info, warn};
+use num_format::{Locale, ToFormattedString};
+use std::fs::metadata;
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+
+pub struct Watched {
+    dataset: String,
+    output_file_path: String,
+    precomputed: PreComputed, // pristine, cloned for each scoring
+    modified: Option<SystemTime>,
+    score: Option<Score>,
+}
+
+impl Watched {
+    pub fn new(input_file_path: &str, output_file_path: &str, precomputed: PreComputed) -> Self {
+        Watched {
+            dataset: dataset_name(input_file_path),
+            output_file_path: output_file_path.to_string(),
+            precomputed,
+            modified: None,
+            score: None,
+        }
+    }
+
+    /// Score and the content that was scored, the file may change again right after.
+    fn rescore(&self, disable_checks: bool) -> anyhow::Result<(Score, String)> {
+        let (output_data, content) = load_output_and_content(&self.output_file_path)?;
+        let mut precomputed = self.precomputed.clone();
+        let score = compute_score_precomputed(&mut precomputed, &output_data, disable_checks)?;
+        Ok((score, content))
+    }
+}
+
+fn format_delta(previous: Option<Score>, current: Option<Score>) -> String {
+    match (previous, current) {
+        (Some(previous), Some(current)) if previous != current => {
+            format!(" ({:+})", current as i64 - previous as i64)
+        }
+        _ => String::new(),
+    }
+}
+
+/// Re-scores output files whenever their modification time changes, never returns.
+///
+/// Inputs are parsed once, output files are polled every `interval`. Scores are recorded in
+/// `history` if there is one.
+pub fn watch(
+    mut watched: Vec<Watched>,
+    disable_checks: bool,
+    interval: Duration,
+    mut history: Option<&mut History>,
+    archive: bool,
+) -> anyhow::Result<()> {
+    let many = watched.len() > 1;
+    loop {
+        let previous_total: Option<Score> = watched.iter().map(|w| w.score).sum();
+        let mut changed = false;
+        for w in watched.iter_mut() {
+            let modified = match metadata(&w.output_file_path).and_then(|m| m.modified()) {
+                Ok(modified) => modified,
+                Err(e) => {
+                    // the solver may be rewriting the file
+                    info!("could not read {}: {}", w.output_file_path, e);
+                    continue;
+                }
+            };
+            if w.modified == Some(modified) {
+                continue;
+            }
+            w.modified = Some(modified);
+            changed = true;
+            match w.rescore(disable_checks) {
+                Ok((score, content)) => {
+                    println!(
+                        "{} score: {}{}",
+                        w.output_file_path,
+                        score.to_formatted_string(&Locale::en),
+                        format_delta(w.score, Some(score))
+                    );
+                    w.score = Some(score);
+                    if let Some(best_path) = try_track(
+                        history.as_deref_mut(),
+                        &w.dataset,
+                        &w.output_file_path,
+                        &content,
+                        score,
+                        archive,
+                    ) {
+                        println!("new best archived to {}", best_path.display());
+                    }
+                }
+                Err(e) => {
+                    warn!("{}: {}", w.output_file_path, e);
+                    println!("{} score: error", w.output_file_path);
+                    w.score = None;
+                }
+            }
+        }
+        if changed && many {
+            let total: Option<Score> = watched.iter().map(|w| w.score).sum();
+            match total {
+                Some(total) => println!(
+                    "total score: {}{}",
+                    total.to_formatted_string(&Locale::en),
+                    format_delta(previous_total, Some(total))
+                ),
+                None => println!("total score: incomplete"),
+            }
+        }
+        sleep(interval);
+    }
+}
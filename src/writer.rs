@@ -0,0 +1,43 @@
+use crate::data::{PInput, POutput};
+use std::io::Write;
+
+/// Writes `input` in the input file format (inverse of `parse_input`).
+pub fn write_input<W: Write>(input: &PInput, writer: &mut W) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        "{} {}",
+        input.contributors.len(),
+        input.projects.len()
+    )?;
+    for contributor in &input.contributors {
+        writeln!(writer, "{} {}", contributor.name, contributor.skills.len())?;
+        for skill in &contributor.skills {
+            writeln!(writer, "{} {}", skill.name, skill.level)?;
+        }
+    }
+    for project in &input.projects {
+        writeln!(
+            writer,
+            "{} {} {} {} {}",
+            project.name,
+            project.days_to_completion,
+            project.score,
+            project.best_before,
+            project.skills.len()
+        )?;
+        for skill in &project.skills {
+            writeln!(writer, "{} {}", skill.name, skill.level)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes `output` in the output file format (inverse of `parse_output`).
+pub fn write_output<W: Write>(output: &POutput, writer: &mut W) -> std::io::Result<()> {
+    writeln!(writer, "{}", output.projects.len())?;
+    for project in &output.projects {
+        writeln!(writer, "{}", project.name)?;
+        writeln!(writer, "{}", project.contributor_names.join(" "))?;
+    }
+    Ok(())
+}
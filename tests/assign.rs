@@ -0,0 +1,148 @@
+mod common;
+
+use common::path;
+use google_hashcode_score_2022::assign::{assign_roles, restaff, Objective};
+use google_hashcode_score_2022::generate::{generate, GeneratorConfig};
+use google_hashcode_score_2022::load::load_precomputed;
+use google_hashcode_score_2022::parser::parse_output;
+use google_hashcode_score_2022::score::{
+    compute_score_precomputed, precompute_from_input, PreComputed,
+};
+
+#[test]
+fn restaff_swapped_roles() {
+    let precomputed = load_precomputed(&path("res/a_an_example.in.txt"), false).unwrap();
+    // Anna has no HTML, Bob no C++
+    let output =
+        parse_output("3\nWebServer\nAnna Bob\nLogging\nAnna\nWebChat\nMaria Bob\n").unwrap();
+    assert!(compute_score_precomputed(&mut precomputed.clone(), &output, false).is_err());
+
+    let (restaffed_output, restaffed) =
+        restaff(&precomputed, &output, Objective::LeastWaste).unwrap();
+    assert_eq!(restaffed.len(), 1);
+    assert_eq!(restaffed[0].project, "WebServer");
+    assert_eq!(restaffed[0].after, vec!["Bob", "Anna"]);
+    assert_eq!(
+        compute_score_precomputed(&mut precomputed.clone(), &restaffed_output, false).unwrap(),
+        33
+    );
+}
+
+fn level(precomputed: &PreComputed, contributor: usize, skill: usize) -> usize {
+    precomputed
+        .levels
+        .get(&(contributor, skill))
+        .cloned()
+        .unwrap_or(0)
+}
+
+/// Cost of a valid assignment for `objective`, `None` if invalid, same rules as the scorer:
+/// (roles that do not level up, waste), only waste counts for `LeastWaste`.
+fn cost(
+    precomputed: &PreComputed,
+    project: usize,
+    team: &[usize],
+    objective: Objective,
+) -> Option<(usize, usize)> {
+    let mut no_level_up = 0;
+    let mut waste = 0;
+    for ((skill, required), contributor) in precomputed.projects[project].skills.iter().zip(team) {
+        let own = level(precomputed, *contributor, *skill);
+        let mentored = own + 1 == *required
+            && team
+                .iter()
+                .any(|c| level(precomputed, *c, *skill) >= *required);
+        if own < *required && !mentored {
+            return None;
+        }
+        if own > *required {
+            no_level_up += 1;
+        }
+        waste += own.saturating_sub(*required);
+    }
+    match objective {
+        Objective::LeastWaste => Some((0, waste)),
+        Objective::MostLevelUps => Some((no_level_up, waste)),
+    }
+}
+
+/// Every assignment of distinct contributors to the roles.
+fn teams(contributors: usize, roles: usize) -> Vec<Vec<usize>> {
+    if roles == 0 {
+        return vec![vec![]];
+    }
+    let mut all = vec![];
+    for team in teams(contributors, roles - 1) {
+        for c in (0..contributors).filter(|c| !team.contains(c)) {
+            let mut team = team.clone();
+            team.push(c);
+            all.push(team);
+        }
+    }
+    all
+}
+
+/// `assign_roles` is optimal whenever the best assignment needs no mentoring, it may miss
+/// assignments relying on mentoring but still beats every assignment without.
+fn matches_brute_force(objective: Objective) {
+    let input = generate(&GeneratorConfig {
+        seed: 3,
+        contributors: 6,
+        projects: 200,
+        skills: 3,
+        max_roles: 3,
+        max_level: 4,
+        ..GeneratorConfig::default()
+    })
+    .unwrap();
+    let precomputed = precompute_from_input(&input);
+    let available: Vec<usize> = (0..precomputed.contributors.len()).collect();
+    let mut optimal = 0;
+    for project in &precomputed.projects {
+        let all = teams(available.len(), project.skills.len());
+        let best = all
+            .iter()
+            .filter_map(|team| cost(&precomputed, project.id, team, objective))
+            .min();
+        let best_without_mentoring = all
+            .iter()
+            .filter(|team| {
+                project
+                    .skills
+                    .iter()
+                    .zip(team.iter())
+                    .all(|((skill, required), c)| level(&precomputed, *c, *skill) >= *required)
+            })
+            .filter_map(|team| cost(&precomputed, project.id, team, objective))
+            .min();
+        let found = assign_roles(&precomputed, project.id, &available, objective).map(|team| {
+            cost(&precomputed, project.id, &team, objective).expect("valid assignment")
+        });
+        if best == best_without_mentoring {
+            assert_eq!(found, best, "project {}", project.name);
+            optimal += 1;
+        } else if let Some(found) = found {
+            assert!(Some(found) >= best, "project {}", project.name);
+            if let Some(best_without_mentoring) = best_without_mentoring {
+                assert!(found <= best_without_mentoring, "project {}", project.name);
+            }
+        } else {
+            assert_eq!(best_without_mentoring, None, "project {}", project.name);
+        }
+    }
+    assert!(
+        optimal > precomputed.projects.len() / 2,
+        "{} optimal",
+        optimal
+    );
+}
+
+#[test]
+fn least_waste_matches_brute_force() {
+    matches_brute_force(Objective::LeastWaste);
+}
+
+#[test]
+fn most_level_ups_matches_brute_force() {
+    matches_brute_force(Objective::MostLevelUps);
+}
@@ -0,0 +1,21 @@
+//! Helpers shared by the integration tests, each test crate uses some of them.
+#![allow(dead_code)]
+
+use std::fs::{create_dir_all, remove_dir_all};
+use std::path::{Path, PathBuf};
+
+/// Path of a file of the repository, tests do not depend on the working directory.
+pub fn path(relative: &str) -> String {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join(relative)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Empty directory for one test, tests run in parallel.
+pub fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("hashcode-{}-{}", name, std::process::id()));
+    let _ = remove_dir_all(&dir);
+    create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use common::path;
+use google_hashcode_score_2022::curve::{score_curve, to_csv, Axis};
+use google_hashcode_score_2022::load::{load_output, load_precomputed};
+use google_hashcode_score_2022::score::{compute_project_scores_precomputed, ProjectScore};
+
+fn example_scores() -> Vec<ProjectScore> {
+    let mut precomputed = load_precomputed(&path("res/a_an_example.in.txt"), false).unwrap();
+    let output = load_output(&path("out/a_an_example.in.txt.out")).unwrap();
+    compute_project_scores_precomputed(&mut precomputed, &output, false).unwrap()
+}
+
+#[test]
+fn example_by_day() {
+    // WebServer ends on day 7, Logging on day 12 and WebChat on day 17
+    let curve = score_curve("a.out", &example_scores(), Axis::Day);
+    assert_eq!(curve.points, vec![(0, 0), (7, 10), (12, 13), (17, 33)]);
+    assert_eq!(
+        to_csv(&[curve], Axis::Day),
+        "output,day,score\na.out,0,0\na.out,7,10\na.out,12,13\na.out,17,33\n"
+    );
+}
+
+#[test]
+fn example_by_project() {
+    let curve = score_curve("out/a, \"best\".out", &example_scores(), Axis::Project);
+    assert_eq!(curve.points, vec![(0, 0), (1, 10), (2, 13), (3, 33)]);
+    let csv = to_csv(&[curve], Axis::Project);
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows[0], "output,project,score");
+    assert_eq!(rows[2], "\"out/a, \"\"best\"\".out\",1,10");
+    assert_eq!(rows.len(), 5);
+}
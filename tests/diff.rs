@@ -0,0 +1,41 @@
+mod common;
+
+use common::{path, test_dir};
+use std::fs::{remove_dir_all, write};
+use std::process::Command;
+
+#[test]
+fn example_diff() {
+    let dir = test_dir("diff");
+    // Logging removed, WebChat added, WebServer re-staffed with Anna on HTML
+    write(dir.join("a.out"), "2\nWebServer\nBob Anna\nLogging\nAnna\n").unwrap();
+    write(
+        dir.join("b.out"),
+        "2\nWebChat\nMaria Bob\nWebServer\nAnna Bob\n",
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_google-hashcode-score-2022"))
+        .current_dir(&dir)
+        .args(["diff", &path("res/a_an_example.in.txt"), "a.out", "b.out"])
+        .arg("--disable-checks")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "a: a.out
+b: b.out
+only in a: Logging
+only in b: WebChat
+reordered (0):
+changed roles (1):
+  WebServer: Bob Anna -> Anna Bob
+score delta (3):
+  WebChat: - -> 20 (+20)
+  WebServer: 10 -> 0 (-10)
+  Logging: 3 -> - (-3)
+total: 13 -> 20 (+7)
+"
+    );
+    remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use common::path;
+use google_hashcode_score_2022::load::{load_output, load_precomputed};
+use google_hashcode_score_2022::parser::{parse_input, parse_output};
+use google_hashcode_score_2022::score::{precompute_from_input, simulate, Blocker, Event};
+
+fn role(
+    project: usize,
+    role: usize,
+    contributor: usize,
+    skill: usize,
+    required: usize,
+    level: usize,
+    mentored: bool,
+) -> Event {
+    Event::RoleFilled {
+        project,
+        role,
+        contributor,
+        skill,
+        required,
+        level,
+        mentored,
+    }
+}
+
+#[test]
+fn example_events() {
+    // projects: Logging 0, WebServer 1, WebChat 2
+    // contributors: Anna 0, Bob 1, Maria 2
+    // skills: C++ 0, HTML 1, CSS 2, Python 3
+    let mut precomputed = load_precomputed(&path("res/a_an_example.in.txt"), false).unwrap();
+    let output = load_output(&path("out/a_an_example.in.txt.out")).unwrap();
+    let mut events = vec![];
+    simulate(&mut precomputed, &output, false, |event| {
+        events.push(event.clone())
+    })
+    .unwrap();
+    let level_up = |contributor, skill, level, day| Event::LevelUp {
+        contributor,
+        skill,
+        level,
+        day,
+    };
+    let ended = |project, day| Event::ProjectEnded { project, day };
+    let awarded = |project, score, max_score, best_before| Event::ScoreAwarded {
+        project,
+        score,
+        max_score,
+        best_before,
+    };
+    assert_eq!(
+        events,
+        vec![
+            Event::ProjectStarted {
+                project: 1,
+                day: 0,
+                blocker: None
+            },
+            role(1, 0, 1, 1, 3, 5, false),
+            role(1, 1, 0, 0, 2, 2, false),
+            ended(1, 7),
+            awarded(1, 10, 10, 7),
+            level_up(0, 0, 3, 7),
+            Event::ProjectStarted {
+                project: 0,
+                day: 7,
+                blocker: Some(Blocker {
+                    role: 0,
+                    contributor: 0,
+                    previous_planned_index: 0
+                })
+            },
+            role(0, 0, 0, 0, 3, 3, false),
+            ended(0, 12),
+            awarded(0, 3, 10, 5),
+            level_up(0, 0, 4, 12),
+            Event::ProjectStarted {
+                project: 2,
+                day: 7,
+                blocker: Some(Blocker {
+                    role: 1,
+                    contributor: 1,
+                    previous_planned_index: 0
+                })
+            },
+            role(2, 0, 2, 3, 3, 3, false),
+            role(2, 1, 1, 1, 3, 5, false),
+            ended(2, 17),
+            awarded(2, 20, 20, 20),
+            level_up(2, 3, 4, 17),
+        ]
+    );
+}
+
+#[test]
+fn mentored_role() {
+    // Bob mentors Anna in C++, only Anna learns
+    let input =
+        parse_input("2 1\nAnna 1\nC++ 2\nBob 1\nC++ 3\nP 5 10 5 2\nC++ 3\nC++ 1\n").unwrap();
+    let output = parse_output("1\nP\nAnna Bob\n").unwrap();
+    let mut events = vec![];
+    simulate(
+        &mut precompute_from_input(&input),
+        &output,
+        false,
+        |event| match event {
+            Event::RoleFilled { .. } | Event::LevelUp { .. } => events.push(event.clone()),
+            _ => {}
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        events,
+        vec![
+            role(0, 0, 0, 0, 3, 2, true),
+            role(0, 1, 1, 0, 1, 3, false),
+            Event::LevelUp {
+                contributor: 0,
+                skill: 0,
+                level: 3,
+                day: 5
+            },
+        ]
+    );
+}
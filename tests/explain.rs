@@ -0,0 +1,62 @@
+mod common;
+
+use common::{path, test_dir};
+use std::fs::{remove_dir_all, write};
+use std::process::{Command, Output};
+
+fn command(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_google-hashcode-score-2022"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn run(args: &[&str]) -> String {
+    let output = command(args);
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn example_late_project() {
+    let input = path("res/a_an_example.in.txt");
+    let output = path("out/a_an_example.in.txt.out");
+
+    assert_eq!(
+        run(&["explain", &input, &output, "Logging"]),
+        "Logging: ends on day 12 (7 days late, best before 5), 7 points lost\n\
+         starts on day 7 because Anna is busy on WebServer until then\n"
+    );
+    assert_eq!(
+        run(&["explain", &input, &output, "WebServer"]),
+        "WebServer: completed in time, full score\n"
+    );
+
+    let report: serde_json::Value =
+        serde_json::from_str(&run(&[&input, "-o", &output, "--json"])).unwrap();
+    let late_projects = report["files"][0]["late_projects"].as_array().unwrap();
+    assert_eq!(late_projects.len(), 1);
+    assert_eq!(late_projects[0]["name"], "Logging");
+    assert_eq!(
+        late_projects[0]["delayed_by"],
+        serde_json::json!({"contributor": "Anna", "previous_project": "WebServer"})
+    );
+}
+
+#[test]
+fn explain_with_checks_disabled() {
+    let dir = test_dir("explain");
+    let input = path("res/a_an_example.in.txt");
+    // Bob does not know C++
+    let output = dir.join("bob.out").to_string_lossy().into_owned();
+    write(&output, "1\nLogging\nBob\n").unwrap();
+
+    assert!(!command(&["explain", &input, &output, "Logging"])
+        .status
+        .success());
+    assert_eq!(
+        run(&["explain", &input, &output, "Logging", "--disable-checks"]),
+        "Logging: completed in time, full score\n"
+    );
+    remove_dir_all(&dir).unwrap();
+}
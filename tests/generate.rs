@@ -0,0 +1,53 @@
+use google_hashcode_score_2022::assign::{assign_roles, Objective};
+use google_hashcode_score_2022::data::{POutput, PPlannedProject};
+use google_hashcode_score_2022::generate::{generate, GeneratorConfig};
+use google_hashcode_score_2022::score::{compute_score_precomputed, precompute_from_input};
+
+#[test]
+fn every_project_can_be_staffed_on_its_own() {
+    // few contributors and skills: roles of a project often compete for the same contributor
+    for seed in 0..20 {
+        let config = GeneratorConfig {
+            seed,
+            contributors: 3,
+            projects: 50,
+            skills: 2,
+            max_skills_per_contributor: 1,
+            max_roles: 3,
+            max_level: 5,
+            ..GeneratorConfig::default()
+        };
+        let input = generate(&config).unwrap();
+        assert_eq!(input, generate(&config).unwrap());
+        let precomputed = precompute_from_input(&input);
+        let available: Vec<usize> = (0..input.contributors.len()).collect();
+        for project in &precomputed.projects {
+            let team = assign_roles(&precomputed, project.id, &available, Objective::LeastWaste)
+                .unwrap_or_else(|| panic!("seed {}: {} cannot be staffed", seed, project.name));
+            let output = POutput {
+                n_projects: 1,
+                projects: vec![PPlannedProject {
+                    name: project.name.clone(),
+                    contributor_names: team
+                        .iter()
+                        .map(|c| input.contributors[*c].name.clone())
+                        .collect(),
+                }],
+            };
+            assert!(compute_score_precomputed(&mut precomputed.clone(), &output, false).is_ok());
+        }
+    }
+}
+
+#[test]
+fn overflowing_workload_is_an_error() {
+    let config = GeneratorConfig {
+        contributors: 2,
+        projects: 4,
+        max_roles: 2,
+        max_days: usize::MAX,
+        ..GeneratorConfig::default()
+    };
+    let error = generate(&config).unwrap_err();
+    assert!(error.to_string().contains("overflows"), "{}", error);
+}
@@ -0,0 +1,82 @@
+//! Known scores for the published example and for the reference outputs in `tests/golden/`.
+//!
+//! Reference outputs were produced by a simple greedy planner and scored by an independent
+//! simulation, any change to these scores means the simulation rules changed.
+
+mod common;
+mod reference;
+
+use common::path;
+use google_hashcode_score_2022::load::{load_output, read_content};
+use google_hashcode_score_2022::parser::parse_input;
+use google_hashcode_score_2022::score::{compute_score_precomputed, precompute_from_input, Score};
+use google_hashcode_score_2022::stream::precompute_from_reader;
+use reference::reference_score;
+
+fn check_golden(input: &str, output: &str, expected: Score) {
+    let input_content = read_content(&path(input)).unwrap();
+    let output = load_output(&path(output)).unwrap();
+    let parsed = parse_input(&input_content).unwrap();
+
+    let mut from_reader = precompute_from_reader(input_content.as_bytes()).unwrap();
+    assert_eq!(
+        compute_score_precomputed(&mut from_reader, &output, false).unwrap(),
+        expected
+    );
+    let mut from_input = precompute_from_input(&parsed);
+    assert_eq!(
+        compute_score_precomputed(&mut from_input, &output, false).unwrap(),
+        expected
+    );
+    assert_eq!(reference_score(&parsed, &output, true), Ok(expected));
+}
+
+#[test]
+fn a_an_example() {
+    check_golden("res/a_an_example.in.txt", "out/a_an_example.in.txt.out", 33);
+}
+
+#[test]
+fn b_better_start_small() {
+    check_golden(
+        "res/b_better_start_small.in.txt",
+        "tests/golden/b_better_start_small.in.txt.out",
+        874_202,
+    );
+}
+
+#[test]
+fn c_collaboration() {
+    check_golden(
+        "res/c_collaboration.in.txt",
+        "tests/golden/c_collaboration.in.txt.out",
+        111_422,
+    );
+}
+
+#[test]
+fn d_dense_schedule() {
+    check_golden(
+        "res/d_dense_schedule.in.txt",
+        "tests/golden/d_dense_schedule.in.txt.out",
+        209_349,
+    );
+}
+
+#[test]
+fn e_exceptional_skills() {
+    check_golden(
+        "res/e_exceptional_skills.in.txt",
+        "tests/golden/e_exceptional_skills.in.txt.out",
+        1_640_454,
+    );
+}
+
+#[test]
+fn f_find_great_mentors() {
+    check_golden(
+        "res/f_find_great_mentors.in.txt",
+        "tests/golden/f_find_great_mentors.in.txt.out",
+        211_629,
+    );
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::{path, test_dir};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use google_hashcode_score_2022::history::{track, History};
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, remove_file, write, File};
+use std::io::{Read, Write};
+use std::process::Command;
+use zip::ZipArchive;
+
+#[test]
+fn only_improvements_are_archived() {
+    let dir = test_dir("track");
+    let history_path = dir.join("history.jsonl");
+    let best_dir = dir.join("best");
+    let best_path = best_dir.join("a.out");
+    let mut history = History::load(&history_path)
+        .unwrap()
+        .with_best_dir(&best_dir);
+
+    let archived = track(&mut history, "a", "a1.out", "first\n", 10, true).unwrap();
+    assert_eq!(archived, Some(best_path.clone()));
+    assert_eq!(read_to_string(&best_path).unwrap(), "first\n");
+
+    // lower or equal scores keep the best output
+    assert_eq!(
+        track(&mut history, "a", "a2.out", "lower\n", 5, true).unwrap(),
+        None
+    );
+    assert_eq!(
+        track(&mut history, "a", "a3.out", "equal\n", 10, true).unwrap(),
+        None
+    );
+    assert_eq!(read_to_string(&best_path).unwrap(), "first\n");
+    // not archived without --archive
+    assert_eq!(
+        track(&mut history, "a", "a4.out", "better\n", 20, false).unwrap(),
+        None
+    );
+    assert_eq!(read_to_string(&best_path).unwrap(), "first\n");
+
+    // a missing best output is archived again, whatever its score
+    remove_file(&best_path).unwrap();
+    let archived = track(&mut history, "a", "a5.out", "again\n", 3, true).unwrap();
+    assert_eq!(archived, Some(best_path.clone()));
+    assert_eq!(read_to_string(&best_path).unwrap(), "again\n");
+
+    // entries survive a reload
+    let history = History::load(&history_path).unwrap();
+    let scores: Vec<(usize, bool)> = history
+        .entries()
+        .iter()
+        .map(|e| (e.score, e.archived))
+        .collect();
+    assert_eq!(
+        scores,
+        vec![(10, true), (5, false), (10, false), (20, false), (3, true)]
+    );
+    remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn archived_gz_output_can_be_packaged() {
+    let dir = test_dir("package");
+    let input = path("res/a_an_example.in.txt");
+    let content = read_to_string(path("out/a_an_example.in.txt.out")).unwrap();
+    let mut encoder = GzEncoder::new(
+        File::create(dir.join("a.out.gz")).unwrap(),
+        Compression::default(),
+    );
+    encoder.write_all(content.as_bytes()).unwrap();
+    encoder.finish().unwrap();
+    create_dir_all(dir.join("src")).unwrap();
+    write(dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+
+    let run = |args: &[&str]| {
+        let status = Command::new(env!("CARGO_BIN_EXE_google-hashcode-score-2022"))
+            .args(args)
+            .current_dir(&dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "{:?} failed", args);
+    };
+    run(&[&input, "-o", "a.out.gz", "--archive"]);
+    // the decompressed text that was scored is archived
+    assert_eq!(
+        read_to_string(dir.join("best/a_an_example.out")).unwrap(),
+        content
+    );
+
+    run(&[
+        "package",
+        &input,
+        "--sources",
+        "src",
+        "-o",
+        "submission.zip",
+    ]);
+    let mut zip = ZipArchive::new(File::open(dir.join("submission.zip")).unwrap()).unwrap();
+    let mut packaged = String::new();
+    zip.by_name("a_an_example.out")
+        .unwrap()
+        .read_to_string(&mut packaged)
+        .unwrap();
+    assert_eq!(packaged, content);
+    remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::test_dir;
+use google_hashcode_score_2022::leaderboard::{Leaderboard, Standing};
+use std::collections::BTreeMap;
+use std::fs::{read_to_string, remove_dir_all};
+
+fn standing(user: &str, best: &[(&str, usize)]) -> Standing {
+    let best: BTreeMap<String, usize> = best.iter().map(|(d, s)| (d.to_string(), *s)).collect();
+    Standing {
+        user: user.to_string(),
+        total_score: best.values().sum(),
+        best,
+    }
+}
+
+#[test]
+fn standings_order_and_ties() {
+    let dir = test_dir("leaderboard");
+    let mut leaderboard = Leaderboard::load(&dir).unwrap();
+    leaderboard.submit("carol", "a", "c1\n", 10).unwrap();
+    leaderboard.submit("bob", "a", "b1\n", 33).unwrap();
+    leaderboard.submit("bob", "a", "b2\n", 20).unwrap(); // worse, best is kept
+    leaderboard.submit("alice", "a", "a1\n", 30).unwrap();
+    leaderboard.submit("alice", "b", "a2\n", 3).unwrap();
+    leaderboard.submit("carol", "b", "c2\n", 30).unwrap();
+
+    // best total first, ties (alice and bob at 33) by user name
+    assert_eq!(
+        leaderboard.standings(),
+        vec![
+            standing("carol", &[("a", 10), ("b", 30)]),
+            standing("alice", &[("a", 30), ("b", 3)]),
+            standing("bob", &[("a", 33)]),
+        ]
+    );
+    assert_eq!(leaderboard.history(None).len(), 6);
+    let bob = leaderboard.history(Some("bob"));
+    assert_eq!(bob.len(), 2);
+    assert_eq!(read_to_string(&bob[1].output).unwrap(), "b2\n");
+
+    assert!(leaderboard.submit("../evil", "a", "x\n", 1).is_err());
+    assert!(leaderboard.submit("", "a", "x\n", 1).is_err());
+    assert_eq!(leaderboard.history(None).len(), 6);
+    remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn submissions_persist_across_restarts() {
+    let dir = test_dir("leaderboard-restart");
+    let mut leaderboard = Leaderboard::load(&dir).unwrap();
+    leaderboard.submit("alice", "a", "a1\n", 30).unwrap();
+    leaderboard.submit("bob", "a", "b1\n", 33).unwrap();
+    let standings = leaderboard.standings();
+    drop(leaderboard);
+
+    let mut leaderboard = Leaderboard::load(&dir).unwrap();
+    assert_eq!(leaderboard.standings(), standings);
+    leaderboard.submit("alice", "b", "a2\n", 5).unwrap();
+    assert_eq!(
+        leaderboard.standings()[0],
+        standing("alice", &[("a", 30), ("b", 5)])
+    );
+    assert_eq!(leaderboard.history(Some("alice")).len(), 2);
+    remove_dir_all(&dir).unwrap();
+}
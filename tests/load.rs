@@ -0,0 +1,116 @@
+mod common;
+
+use common::{path, test_dir};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use google_hashcode_score_2022::load::{load_precomputed, read_content};
+use google_hashcode_score_2022::score::CACHE_HEADER;
+use std::fs::{read, read_to_string, remove_dir_all, write, File};
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+use xz2::write::XzEncoder;
+
+const EXAMPLE_OUTPUT: &str = "out/a_an_example.in.txt.out";
+
+/// Runs the binary with `stdin` as its standard input.
+fn run_with_stdin(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_google-hashcode-score-2022"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // the process may exit before reading stdin
+    let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn compressed_inputs() {
+    let dir = test_dir("load");
+    let input = read_to_string(path("res/a_an_example.in.txt")).unwrap();
+    let gz_path = dir.join("a.in.txt.gz");
+    let mut gz = GzEncoder::new(File::create(&gz_path).unwrap(), Compression::default());
+    gz.write_all(input.as_bytes()).unwrap();
+    gz.finish().unwrap();
+    let xz_path = dir.join("a.in.txt.xz");
+    let mut xz = XzEncoder::new(File::create(&xz_path).unwrap(), 6);
+    xz.write_all(input.as_bytes()).unwrap();
+    xz.finish().unwrap();
+
+    for compressed in [&gz_path, &xz_path] {
+        let compressed = compressed.to_string_lossy();
+        assert_eq!(read_content(&compressed).unwrap(), input);
+        let precomputed = load_precomputed(&compressed, false).unwrap();
+        assert_eq!(precomputed.projects.len(), 3);
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_google-hashcode-score-2022"))
+        .arg(&xz_path)
+        .args(["-o", &path(EXAMPLE_OUTPUT)])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .ends_with("score: 33\n"));
+    remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn stdin_input_or_output() {
+    let input = read_to_string(path("res/a_an_example.in.txt")).unwrap();
+    let output = read_to_string(path(EXAMPLE_OUTPUT)).unwrap();
+
+    let result = run_with_stdin(&[&path("res/a_an_example.in.txt"), "-o", "-"], &output);
+    assert!(result.status.success());
+    assert_eq!(String::from_utf8(result.stdout).unwrap(), "- score: 33\n");
+
+    let result = run_with_stdin(&["-", "-o", &path(EXAMPLE_OUTPUT)], &input);
+    assert!(result.status.success());
+    assert!(String::from_utf8(result.stdout)
+        .unwrap()
+        .ends_with("score: 33\n"));
+}
+
+#[test]
+fn stdin_is_read_once() {
+    let output = read_to_string(path(EXAMPLE_OUTPUT)).unwrap();
+    for args in [
+        vec!["-", "-o", "-"],
+        vec!["diff", "res/a_an_example.in.txt", "-", "-"],
+    ] {
+        let result = run_with_stdin(&args, &output);
+        assert!(!result.status.success());
+        assert!(String::from_utf8_lossy(&result.stderr).contains("stdin (-) can only be used once"));
+    }
+}
+
+#[test]
+fn cache_files_are_versioned() {
+    let dir = test_dir("load-cache");
+    let input_path = dir.join("a.in.txt");
+    write(
+        &input_path,
+        read_to_string(path("res/a_an_example.in.txt")).unwrap(),
+    )
+    .unwrap();
+    let parsed = load_precomputed(&input_path.to_string_lossy(), true).unwrap();
+    let bin_path = dir.join("a.in.bin");
+    let cached = load_precomputed(&bin_path.to_string_lossy(), false).unwrap();
+    assert_eq!(cached.projects.len(), parsed.projects.len());
+    assert_eq!(cached.contributors.len(), parsed.contributors.len());
+
+    // e.g. a cache file generated before versioning
+    let mut content = read(&bin_path).unwrap();
+    content[CACHE_HEADER.len() - 1] ^= 0xff;
+    write(&bin_path, &content).unwrap();
+    let error = load_precomputed(&bin_path.to_string_lossy(), false).unwrap_err();
+    assert!(
+        error.to_string().contains("regenerate it with --cache"),
+        "{}",
+        error
+    );
+    remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::path;
+use google_hashcode_score_2022::load::{load_output, load_precomputed};
+use google_hashcode_score_2022::marginal::leave_one_out;
+use google_hashcode_score_2022::score::compute_score_precomputed;
+
+/// Every delta against the whole output simulated again without the project.
+fn check_against_removal(dataset: &str) {
+    let precomputed = load_precomputed(&path(&format!("res/{}", dataset)), false).unwrap();
+    let output = load_output(&path(&format!("tests/golden/{}.out", dataset))).unwrap();
+    let total = compute_score_precomputed(&mut precomputed.clone(), &output, false).unwrap();
+    let marginals = leave_one_out(&precomputed, &output, false).unwrap();
+    assert_eq!(marginals.len(), output.projects.len());
+    for (index, marginal) in marginals.iter().enumerate() {
+        let mut without = output.clone();
+        without.projects.remove(index);
+        without.n_projects -= 1;
+        let expected = compute_score_precomputed(&mut precomputed.clone(), &without, false)
+            .ok()
+            .map(|score| score as i64 - total as i64);
+        assert_eq!(
+            marginal.delta, expected,
+            "{}: removing {}",
+            dataset, marginal.name
+        );
+    }
+}
+
+#[test]
+fn agrees_with_removal_on_b() {
+    check_against_removal("b_better_start_small.in.txt");
+}
+
+#[test]
+fn agrees_with_removal_on_d() {
+    check_against_removal("d_dense_schedule.in.txt");
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::path;
+use fxhash::FxHashMap;
+use google_hashcode_score_2022::generate::{generate, GeneratorConfig};
+use google_hashcode_score_2022::load::read_content;
+use google_hashcode_score_2022::mip::{build_model, import_solution, parse_solution};
+use google_hashcode_score_2022::parser::parse_input;
+use google_hashcode_score_2022::score::{compute_score_precomputed, precompute_from_input};
+
+fn ones(names: &[&str]) -> FxHashMap<String, f64> {
+    names.iter().map(|name| (name.to_string(), 1.0)).collect()
+}
+
+#[test]
+fn example_solution() {
+    let input = parse_input(&read_content(&path("res/a_an_example.in.txt")).unwrap()).unwrap();
+    let model = build_model(&input, None).unwrap();
+    // WebServer on day 0 with Bob and Anna, WebChat on day 7 with Maria and Bob
+    let mut values = ones(&[
+        "x_1_0", "y_1_0_1", "y_1_1_0", "w_1_0_0", "w_1_1_0", "x_2_7", "y_2_0_2", "y_2_1_1",
+        "w_2_1_7", "w_2_2_7",
+    ]);
+    assert_eq!(model.objective_value(&values).unwrap(), 30);
+
+    let output = import_solution(&input, &values).unwrap();
+    assert_eq!(output.projects[0].name, "WebServer");
+    assert_eq!(output.projects[0].contributor_names, vec!["Bob", "Anna"]);
+    assert_eq!(output.projects[1].name, "WebChat");
+    assert_eq!(output.projects[1].contributor_names, vec!["Maria", "Bob"]);
+    let mut precomputed = precompute_from_input(&input);
+    assert!(compute_score_precomputed(&mut precomputed, &output, false).unwrap() >= 30);
+
+    // Bob on both projects at the same time
+    values.remove("x_2_7");
+    values.remove("w_2_1_7");
+    values.remove("w_2_2_7");
+    values.extend(ones(&["x_2_5", "w_2_1_5", "w_2_2_5"]));
+    assert!(model.objective_value(&values).is_err());
+}
+
+#[test]
+fn solution_formats() {
+    // Gurobi .sol and CBC solution lines
+    let values = parse_solution(
+        "# Objective value = 30\nx_1_0 1\ny_1_0_1 1\nw_1_1_0 1\nOptimal - objective value 30.00000000\n      12 x_2_7   1    -20\n      40 y_2_1_1   1   0\n",
+    )
+    .unwrap();
+    assert_eq!(values.len(), 4);
+    assert_eq!(values["x_1_0"], 1.0);
+    assert_eq!(values["x_2_7"], 1.0);
+    assert_eq!(values["y_2_1_1"], 1.0);
+    assert!(parse_solution("x_1_0 one\n").is_err());
+}
+
+#[test]
+fn large_models_are_refused() {
+    let input = generate(&GeneratorConfig {
+        projects: 1000,
+        max_days: 1000,
+        ..GeneratorConfig::default()
+    })
+    .unwrap();
+    let error = build_model(&input, None).unwrap_err().to_string();
+    assert!(error.contains("--horizon"), "{}", error);
+    assert!(build_model(&input, Some(50)).is_ok());
+}
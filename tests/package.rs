@@ -0,0 +1,75 @@
+mod common;
+
+use common::test_dir;
+use google_hashcode_score_2022::package::{package, ValidOutput, MANIFEST};
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::io::Read;
+use zip::ZipArchive;
+
+fn valid_output(dataset: &str, content: &str, score: usize) -> ValidOutput {
+    ValidOutput {
+        dataset: dataset.to_string(),
+        content: content.to_string(),
+        score,
+    }
+}
+
+fn entry(archive: &mut ZipArchive<std::fs::File>, name: &str) -> String {
+    let mut content = String::new();
+    archive
+        .by_name(name)
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    content
+}
+
+#[test]
+fn validated_content_is_packaged() {
+    let dir = test_dir("package");
+    let sources = dir.join("sources");
+    create_dir_all(sources.join("src")).unwrap();
+    write(sources.join("src/main.rs"), "fn main() {}\n").unwrap();
+    write(sources.join(".hidden"), "skipped\n").unwrap();
+    let zip_path = sources.join("submission.zip");
+
+    let outputs = [
+        valid_output("a_an_example", "1\nLogging\nAnna\n", 3),
+        valid_output("b_better_start_small", "0\n", 0),
+    ];
+    let manifest = package(&outputs, &sources, &zip_path).unwrap();
+    assert_eq!(manifest.total_score, 3);
+
+    let mut archive = ZipArchive::new(std::fs::File::open(&zip_path).unwrap()).unwrap();
+    let mut names: Vec<_> = archive.file_names().map(String::from).collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "a_an_example.out",
+            "b_better_start_small.out",
+            MANIFEST,
+            "sources/src/main.rs"
+        ]
+    );
+    assert_eq!(
+        entry(&mut archive, "a_an_example.out"),
+        "1\nLogging\nAnna\n"
+    );
+    assert_eq!(entry(&mut archive, "sources/src/main.rs"), "fn main() {}\n");
+    remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn duplicate_datasets_are_rejected() {
+    let dir = test_dir("package-duplicate");
+    let zip_path = dir.join("submission.zip");
+    let outputs = [
+        valid_output("a_an_example", "1\nLogging\nAnna\n", 3),
+        valid_output("a_an_example", "0\n", 0),
+    ];
+    let error = package(&outputs, &dir, &zip_path).unwrap_err();
+    assert_eq!(error.to_string(), "dataset a_an_example provided twice");
+    assert!(!zip_path.exists());
+    remove_dir_all(&dir).unwrap();
+}
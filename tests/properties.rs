@@ -0,0 +1,284 @@
+mod reference;
+
+use google_hashcode_score_2022::assign::Objective;
+use google_hashcode_score_2022::data::{
+    PContributor, PContributorSkill, PInput, POutput, PPlannedProject, PProject,
+};
+use google_hashcode_score_2022::levels::{HashLevelMap, LevelMap};
+use google_hashcode_score_2022::marginal::leave_one_out;
+use google_hashcode_score_2022::parser::parse_input;
+use google_hashcode_score_2022::reorder::{reorder, ReorderConfig};
+use google_hashcode_score_2022::repair::repair;
+use google_hashcode_score_2022::score::{compute_score_precomputed, precompute_from_input};
+use google_hashcode_score_2022::stream::precompute_from_reader;
+use google_hashcode_score_2022::writer::write_input;
+use proptest::collection::vec;
+use proptest::option;
+use proptest::prelude::*;
+use reference::reference_score;
+
+fn skill(skill: usize, level: usize) -> PContributorSkill {
+    PContributorSkill {
+        name: format!("s{}", skill),
+        level,
+    }
+}
+
+/// Small instances: few skills and low levels so that random teams are often valid.
+fn instance() -> impl Strategy<Value = PInput> {
+    (1..=3usize).prop_flat_map(|n_skills| {
+        let contributor = vec(option::weighted(0.7, 0..=3usize), n_skills);
+        let project = (
+            1..=5usize,
+            1..=10usize,
+            0..=15usize,
+            vec((0..n_skills, 0..=3usize), 1..=3),
+        );
+        (vec(contributor, 1..=5), vec(project, 1..=5)).prop_map(|(contributors, projects)| {
+            let contributors: Vec<PContributor> = contributors
+                .into_iter()
+                .enumerate()
+                .map(|(id, levels)| {
+                    let skills: Vec<PContributorSkill> = levels
+                        .into_iter()
+                        .enumerate()
+                        .filter_map(|(s, level)| level.map(|level| skill(s, level)))
+                        .collect();
+                    PContributor {
+                        name: format!("c{}", id),
+                        n_skills: skills.len(),
+                        skills,
+                        id,
+                    }
+                })
+                .collect();
+            let projects: Vec<PProject> = projects
+                .into_iter()
+                .enumerate()
+                .map(
+                    |(id, (days_to_completion, score, best_before, roles))| PProject {
+                        name: format!("p{}", id),
+                        days_to_completion,
+                        score,
+                        best_before,
+                        n_roles: roles.len(),
+                        skills: roles
+                            .into_iter()
+                            .map(|(s, level)| skill(s, level))
+                            .collect(),
+                        id,
+                    },
+                )
+                .collect();
+            PInput {
+                n_contributors: contributors.len(),
+                n_projects: projects.len(),
+                contributors,
+                projects,
+            }
+        })
+    })
+}
+
+#[derive(Debug, Clone)]
+enum Corruption {
+    None,
+    PlanProjectTwice,
+    UnknownProject,
+    MissingContributor,
+    ExtraContributor,
+    ContributorTwice,
+    UnknownContributor,
+}
+
+fn corruption() -> impl Strategy<Value = Corruption> {
+    prop_oneof![
+        6 => Just(Corruption::None),
+        1 => Just(Corruption::PlanProjectTwice),
+        1 => Just(Corruption::UnknownProject),
+        1 => Just(Corruption::MissingContributor),
+        1 => Just(Corruption::ExtraContributor),
+        1 => Just(Corruption::ContributorTwice),
+        1 => Just(Corruption::UnknownContributor),
+    ]
+}
+
+/// Random projects order and random teams of the right size, then maybe one corruption.
+fn instance_and_output() -> impl Strategy<Value = (PInput, POutput)> {
+    instance()
+        .prop_flat_map(|input| {
+            let n_projects = input.projects.len();
+            let contributors: Vec<usize> = (0..input.contributors.len()).collect();
+            (
+                Just((0..n_projects).collect::<Vec<usize>>()).prop_shuffle(),
+                0..=n_projects,
+                vec(Just(contributors).prop_shuffle(), n_projects),
+                corruption(),
+                Just(input),
+            )
+        })
+        .prop_map(|(order, n_planned, teams, corruption, input)| {
+            let mut projects: Vec<PPlannedProject> = order[..n_planned]
+                .iter()
+                .zip(teams)
+                .map(|(p, team)| {
+                    let n_roles = input.projects[*p].skills.len();
+                    PPlannedProject {
+                        name: input.projects[*p].name.clone(),
+                        contributor_names: team
+                            .iter()
+                            .take(n_roles)
+                            .map(|c| input.contributors[*c].name.clone())
+                            .collect(),
+                    }
+                })
+                .collect();
+            let first = projects.first().map(|p| p.name.clone());
+            if let (Some(first), Some(last)) = (first, projects.last_mut()) {
+                match corruption {
+                    Corruption::None => {}
+                    Corruption::PlanProjectTwice => last.name = first,
+                    Corruption::UnknownProject => last.name = String::from("unknown"),
+                    Corruption::MissingContributor => {
+                        last.contributor_names.pop();
+                    }
+                    Corruption::ExtraContributor => last.contributor_names.push(String::from("c0")),
+                    Corruption::ContributorTwice => {
+                        let first = last.contributor_names[0].clone();
+                        last.contributor_names.push(first);
+                    }
+                    Corruption::UnknownContributor => {
+                        last.contributor_names[0] = String::from("nobody")
+                    }
+                }
+            }
+            let output = POutput {
+                n_projects: projects.len(),
+                projects,
+            };
+            (input, output)
+        })
+}
+
+/// Scores `output` with both input loading paths, they must agree with each other.
+fn score(input: &PInput, output: &POutput, disable_checks: bool) -> Option<usize> {
+    let mut text = vec![];
+    write_input(input, &mut text).unwrap();
+    let parsed = parse_input(std::str::from_utf8(&text).unwrap()).unwrap();
+    let from_input =
+        compute_score_precomputed(&mut precompute_from_input(&parsed), output, disable_checks).ok();
+    let from_reader = compute_score_precomputed(
+        &mut precompute_from_reader(text.as_slice()).unwrap(),
+        output,
+        disable_checks,
+    )
+    .ok();
+    assert_eq!(from_input, from_reader);
+    from_input
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(2000))]
+
+    #[test]
+    fn agrees_with_reference((input, output) in instance_and_output()) {
+        let expected = reference_score(&input, &output, true);
+        prop_assert_eq!(score(&input, &output, false), expected.clone().ok(), "reference: {:?}", expected);
+    }
+
+    #[test]
+    fn agrees_with_reference_without_level_checks((input, output) in instance_and_output()) {
+        let expected = reference_score(&input, &output, false);
+        prop_assert_eq!(score(&input, &output, true), expected.clone().ok(), "reference: {:?}", expected);
+    }
+
+    #[test]
+    fn leave_one_out_agrees_with_removal((input, output) in instance_and_output(), disable_checks in any::<bool>()) {
+        if let Ok(total) = reference_score(&input, &output, !disable_checks) {
+            let marginals = leave_one_out(&precompute_from_input(&input), &output, disable_checks).unwrap();
+            for (index, marginal) in marginals.iter().enumerate() {
+                let mut without = output.clone();
+                without.projects.remove(index);
+                without.n_projects -= 1;
+                let expected = reference_score(&input, &without, !disable_checks)
+                    .ok()
+                    .map(|score| score as i64 - total as i64);
+                prop_assert_eq!(marginal.delta, expected, "removing {}", index);
+            }
+        }
+    }
+
+    #[test]
+    fn repair_gives_a_valid_output((input, output) in instance_and_output(), objective in prop_oneof![Just(Objective::LeastWaste), Just(Objective::MostLevelUps)]) {
+        let repaired = repair(&precompute_from_input(&input), &output, objective).unwrap();
+        prop_assert_eq!(reference_score(&input, &repaired.output, true), Ok(repaired.score));
+        if let Ok(score) = reference_score(&input, &output, true) {
+            prop_assert!(repaired.score >= score);
+        }
+    }
+
+    #[test]
+    fn reorder_keeps_teams_and_validity((input, output) in instance_and_output(), seed in any::<u64>()) {
+        if let Ok(score) = reference_score(&input, &output, true) {
+            let config = ReorderConfig { seed, iterations: 20 };
+            let reordered = reorder(&precompute_from_input(&input), &output, &config).unwrap();
+            prop_assert_eq!(reordered.initial_score, score);
+            prop_assert_eq!(reference_score(&input, &reordered.output, true), Ok(reordered.score));
+            prop_assert!(reordered.score >= score);
+            let mut before = output.projects.clone();
+            let mut after = reordered.output.projects.clone();
+            before.sort_by(|a, b| a.name.cmp(&b.name));
+            after.sort_by(|a, b| a.name.cmp(&b.name));
+            prop_assert_eq!(before, after);
+        }
+    }
+
+    #[test]
+    fn parsers_accept_the_same_inputs(
+        input in instance(),
+        edits in vec((any::<prop::sample::Index>(), option::of(prop::sample::select(vec![' ', '\n', '\r', '\t', '0', '7', '+', 'x']))), 1..4),
+    ) {
+        let mut text = Vec::new();
+        write_input(&input, &mut text).unwrap();
+        let mut text = String::from_utf8(text).unwrap();
+        for (index, edit) in edits {
+            let position = index.index(text.len() + 1);
+            match edit {
+                Some(c) => text.insert(position, c),
+                None if position < text.len() => {
+                    text.remove(position);
+                }
+                None => {}
+            }
+        }
+        prop_assert_eq!(
+            parse_input(&text).is_ok(),
+            precompute_from_reader(text.as_bytes()).is_ok(),
+            "{:?}",
+            text
+        );
+    }
+
+    #[test]
+    fn level_map_agrees_with_hash_map(inserts in vec((0..20usize, 0..10usize, 0..5usize), 0..200)) {
+        let mut levels = LevelMap::default();
+        let mut expected = HashLevelMap::default();
+        for (contributor, skill, level) in inserts {
+            prop_assert_eq!(
+                levels.insert((contributor, skill), level),
+                expected.insert((contributor, skill), level)
+            );
+        }
+        prop_assert_eq!(levels.len(), expected.len());
+        for (key, level) in &expected {
+            prop_assert_eq!(levels.get(key), Some(level));
+        }
+        levels.retain(|_, level| *level > 2);
+        expected.retain(|_, level| *level > 2);
+        let mut entries: Vec<_> = levels.iter().map(|(key, level)| (key, *level)).collect();
+        let mut expected: Vec<_> = expected.into_iter().collect();
+        entries.sort_unstable();
+        expected.sort_unstable();
+        prop_assert_eq!(entries, expected);
+    }
+}
@@ -0,0 +1,88 @@
+//! Deliberately naive implementation of the scoring rules, written straight from
+//! the problem statement: names everywhere, linear searches, no precomputation.
+
+use google_hashcode_score_2022::data::{PInput, POutput};
+use std::collections::{HashMap, HashSet};
+
+/// Score of `output`, or the reason it is rejected.
+///
+/// With `check_levels` false, skill levels are not checked (same as `--disable-checks`)
+/// but the output must still be well formed.
+pub fn reference_score(
+    input: &PInput,
+    output: &POutput,
+    check_levels: bool,
+) -> Result<usize, String> {
+    // (contributor, skill) -> level, missing means 0
+    let mut levels: HashMap<(String, String), usize> = HashMap::new();
+    for contributor in &input.contributors {
+        for skill in &contributor.skills {
+            levels.insert((contributor.name.clone(), skill.name.clone()), skill.level);
+        }
+    }
+    let level = |levels: &HashMap<(String, String), usize>, contributor: &str, skill: &str| {
+        *levels
+            .get(&(contributor.to_string(), skill.to_string()))
+            .unwrap_or(&0)
+    };
+    let mut available_at: HashMap<String, usize> = HashMap::new();
+    let mut planned: HashSet<String> = HashSet::new();
+    let mut score = 0;
+
+    for assignment in &output.projects {
+        let project = input
+            .projects
+            .iter()
+            .find(|p| p.name == assignment.name)
+            .ok_or_else(|| format!("unknown project {}", assignment.name))?;
+        if !planned.insert(project.name.clone()) {
+            return Err(format!("project {} planned twice", project.name));
+        }
+        let names = &assignment.contributor_names;
+        if names.len() != project.skills.len() {
+            return Err(format!("wrong number of contributors for {}", project.name));
+        }
+        for (i, name) in names.iter().enumerate() {
+            if !input.contributors.iter().any(|c| &c.name == name) {
+                return Err(format!("unknown contributor {}", name));
+            }
+            if names[..i].contains(name) {
+                return Err(format!("contributor {} assigned twice", name));
+            }
+        }
+
+        if check_levels {
+            for (i, role) in project.skills.iter().enumerate() {
+                let own = level(&levels, &names[i], &role.name);
+                let mentored = names
+                    .iter()
+                    .enumerate()
+                    .any(|(j, other)| j != i && level(&levels, other, &role.name) >= role.level);
+                if own < role.level && !(own + 1 == role.level && mentored) {
+                    return Err(format!("{} cannot fill role {}", names[i], i));
+                }
+            }
+        }
+
+        let start = names
+            .iter()
+            .map(|name| *available_at.get(name).unwrap_or(&0))
+            .max()
+            .unwrap_or(0);
+        let end = start + project.days_to_completion;
+        score += if end <= project.best_before {
+            project.score
+        } else {
+            project.score.saturating_sub(end - project.best_before)
+        };
+
+        for (i, role) in project.skills.iter().enumerate() {
+            available_at.insert(names[i].clone(), end);
+            let own = level(&levels, &names[i], &role.name);
+            if own <= role.level {
+                levels.insert((names[i].clone(), role.name.clone()), own + 1);
+            }
+        }
+    }
+    Ok(score)
+}
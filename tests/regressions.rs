@@ -0,0 +1,94 @@
+//! Inputs that used to crash the parsers or the scorer, see the fuzz targets in `fuzz/`.
+
+use google_hashcode_score_2022::parser::{parse_input, parse_output};
+use google_hashcode_score_2022::score::{compute_score_precomputed, precompute_from_input};
+use google_hashcode_score_2022::stream::precompute_from_reader;
+
+fn score(input: &str, output: &str) -> anyhow::Result<usize> {
+    let output = parse_output(output)?;
+    let from_input = compute_score_precomputed(
+        &mut precompute_from_input(&parse_input(input)?),
+        &output,
+        false,
+    );
+    let from_reader = compute_score_precomputed(
+        &mut precompute_from_reader(input.as_bytes())?,
+        &output,
+        false,
+    );
+    assert_eq!(from_input.as_ref().ok(), from_reader.as_ref().ok());
+    from_reader
+}
+
+#[test]
+fn huge_counts_are_not_preallocated() {
+    assert!(parse_input("1000000000000000000 1\n").is_err());
+    assert!(parse_input("1 0\nAnna 1000000000000000000\n").is_err());
+    assert!(parse_input("0 1\nLogging 5 10 5 1000000000000000000\n").is_err());
+    assert!(parse_output("3333333333333333\nW").is_err());
+    assert!(precompute_from_reader("1000000000000000000 1\n".as_bytes()).is_err());
+}
+
+#[test]
+fn numbers_too_large_are_rejected() {
+    assert!(parse_input("18446744073709551616 0\n").is_err());
+    assert!(precompute_from_reader("18446744073709551616 0\n".as_bytes()).is_err());
+}
+
+#[test]
+fn end_time_does_not_overflow() {
+    let input = "1 2\nA 1\nS 1\nP 18446744073709551615 1 1 1\nS 1\nQ 1 1 1 1\nS 1\n";
+    assert_eq!(score(input, "2\nP\nA\nQ\nA\n").unwrap(), 0);
+}
+
+#[test]
+fn very_late_project_scores_nothing() {
+    let input = "1 1\nA 1\nS 1\nP 18446744073709551615 5 1 1\nS 1\n";
+    assert_eq!(score(input, "1\nP\nA\n").unwrap(), 0);
+}
+
+#[test]
+fn large_score_one_day_late() {
+    let input = "1 1\nA 1\nS 1\nP 2 18446744073709551615 1 1\nS 1\n";
+    assert_eq!(score(input, "1\nP\nA\n").unwrap(), 18446744073709551614);
+}
+
+#[test]
+fn total_score_overflow_is_rejected() {
+    let input = "1 2\nA 1\nS 1\nP 1 18446744073709551615 1 1\nS 1\nQ 1 1 2 1\nS 1\n";
+    assert!(score(input, "2\nP\nA\nQ\nA\n").is_err());
+}
+
+#[test]
+fn level_does_not_overflow() {
+    let input = "1 1\nA 1\nS 18446744073709551615\nP 1 1 1 1\nS 18446744073709551615\n";
+    assert_eq!(score(input, "1\nP\nA\n").unwrap(), 1);
+}
+
+#[test]
+fn both_parsers_reject_the_same_malformed_inputs() {
+    let valid = "1 1\nAnna 1\nC++ 2\nLogging 5 10 5 1\nC++ 3\n";
+    assert!(parse_input(valid).is_ok());
+    assert!(precompute_from_reader(valid.as_bytes()).is_ok());
+    for malformed in [
+        "1 1\nAnna 1 extra\nC++ 2\nLogging 5 10 5 1\nC++ 3\n",
+        "1 1\nAnna 1\nC++ 2\nLogging 5 10 5 1 extra\nC++ 3\n",
+        "1 1 1\nAnna 1\nC++ 2\nLogging 5 10 5 1\nC++ 3\n",
+        "1 1\nAnna 1\nC++ 2 \nLogging 5 10 5 1\nC++ 3\n",
+        "1  1\nAnna 1\nC++ 2\nLogging 5 10 5 1\nC++ 3\n",
+        " 1 1\nAnna 1\nC++ 2\nLogging 5 10 5 1\nC++ 3\n",
+        "1\t1\nAnna 1\nC++ 2\nLogging 5 10 5 1\nC++ 3\n",
+        "1 1\nAnna +1\nC++ 2\nLogging 5 10 5 1\nC++ 3\n",
+        "1 1\nAnna 1\nC++ 2\nLogging 5 10 5\nC++ 3\n",
+        "1 1\nAnna 1\nC++ 2\nLogging 5 10 5 1\nC++ 3",
+        "1 1\nAnna 1\nC++ 2\r\r\nLogging 5 10 5 1\nC++ 3\n",
+        "1 1\nAnna 1\nC++ 2\nLogging 5 10 5 1\n",
+    ] {
+        assert!(parse_input(malformed).is_err(), "{:?}", malformed);
+        assert!(
+            precompute_from_reader(malformed.as_bytes()).is_err(),
+            "{:?}",
+            malformed
+        );
+    }
+}
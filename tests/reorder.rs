@@ -0,0 +1,27 @@
+mod common;
+
+use common::path;
+use google_hashcode_score_2022::load::{load_output, load_precomputed};
+use google_hashcode_score_2022::reorder::{dependencies, reorder, ReorderConfig};
+
+#[test]
+fn example_dependencies() {
+    let precomputed = load_precomputed(&path("res/a_an_example.in.txt"), false).unwrap();
+    let output = load_output(&path("out/a_an_example.in.txt.out")).unwrap();
+    // Anna needs C++ 3 on Logging, she gets it on WebServer
+    assert_eq!(
+        dependencies(&precomputed, &output).unwrap(),
+        vec![vec![], vec![0], vec![]]
+    );
+    let reordered = reorder(&precomputed, &output, &ReorderConfig::default()).unwrap();
+    assert_eq!(reordered.score, 33);
+    assert_eq!(reordered.output.projects[0].name, "WebServer");
+}
+
+#[test]
+fn invalid_output_is_rejected() {
+    let precomputed = load_precomputed(&path("res/a_an_example.in.txt"), false).unwrap();
+    let mut output = load_output(&path("out/a_an_example.in.txt.out")).unwrap();
+    output.projects.swap(0, 1);
+    assert!(reorder(&precomputed, &output, &ReorderConfig::default()).is_err());
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::path;
+use google_hashcode_score_2022::assign::{Objective, Restaffed};
+use google_hashcode_score_2022::load::load_precomputed;
+use google_hashcode_score_2022::parser::parse_output;
+use google_hashcode_score_2022::repair::{repair, Change};
+
+#[test]
+fn repair_example() {
+    let precomputed = load_precomputed(&path("res/a_an_example.in.txt"), false).unwrap();
+    let output = parse_output(
+        "5\nWebServer\nAnna Bob\nNope\nAnna\nLogging\nAnna Zed\nWebChat\nMaria Bob Bob\nWebChat\nMaria\n",
+    )
+    .unwrap();
+    let repaired = repair(&precomputed, &output, Objective::MostLevelUps).unwrap();
+    assert_eq!(repaired.score, 33);
+    assert_eq!(
+        repaired.changes,
+        vec![
+            Change::UnknownProject {
+                project: "Nope".to_string()
+            },
+            Change::UnknownContributor {
+                project: "Logging".to_string(),
+                contributor: "Zed".to_string()
+            },
+            Change::DuplicateContributor {
+                project: "WebChat".to_string(),
+                contributor: "Bob".to_string()
+            },
+            Change::DuplicateProject {
+                project: "WebChat".to_string()
+            },
+            Change::Restaffed(Restaffed {
+                project: "WebServer".to_string(),
+                before: vec!["Anna".to_string(), "Bob".to_string()],
+                after: vec!["Bob".to_string(), "Anna".to_string()],
+            }),
+        ]
+    );
+}
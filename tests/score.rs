@@ -0,0 +1,55 @@
+mod common;
+
+use common::path;
+use google_hashcode_score_2022::parser::{parse_input, parse_output};
+use google_hashcode_score_2022::score::{compute_score_precomputed, precompute_from_input};
+use std::fs::read_to_string;
+
+fn score(input: &str, output: &str, disable_checks: bool) -> anyhow::Result<usize> {
+    let mut precomputed = precompute_from_input(&parse_input(input).unwrap());
+    compute_score_precomputed(&mut precomputed, &parse_output(output)?, disable_checks)
+}
+
+/// Structural errors are rejected whether level checks are enabled or not.
+fn assert_rejected(output: &str, message: &str) {
+    let input = read_to_string(path("res/a_an_example.in.txt")).unwrap();
+    for disable_checks in [false, true] {
+        let error = score(&input, output, disable_checks).unwrap_err();
+        assert!(error.to_string().contains(message), "{}", error);
+    }
+}
+
+#[test]
+fn project_planned_twice() {
+    assert_rejected(
+        "2\nWebServer\nBob Anna\nWebServer\nBob Anna\n",
+        "project WebServer is planned more than once",
+    );
+}
+
+#[test]
+fn wrong_number_of_contributors() {
+    assert_rejected(
+        "1\nWebServer\nBob\n",
+        "project WebServer has 2 roles but 1 contributors are assigned",
+    );
+    assert_rejected(
+        "1\nLogging\nAnna Bob\n",
+        "project Logging has 1 roles but 2 contributors are assigned",
+    );
+}
+
+#[test]
+fn contributor_twice_in_a_project() {
+    assert_rejected(
+        "1\nWebServer\nBob Bob\n",
+        "contributor Bob is assigned more than once to project WebServer",
+    );
+}
+
+#[test]
+fn mentored_level_0_role() {
+    // Bob does not know Python, the level 0 role is mentored by nobody
+    let input = "2 1\nAnna 1\nC++ 1\nBob 1\nHTML 1\nP 5 10 5 2\nC++ 1\nPython 0\n";
+    assert_eq!(score(input, "1\nP\nAnna Bob\n", false).unwrap(), 10);
+}
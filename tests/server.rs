@@ -0,0 +1,151 @@
+mod common;
+
+use common::{path, test_dir};
+use google_hashcode_score_2022::server::MAX_BODY_SIZE;
+use std::fs::remove_dir_all;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+
+/// `serve` child process on a free port, killed when dropped.
+struct Server {
+    child: Child,
+    port: u16,
+}
+
+impl Server {
+    fn start(leaderboard_dir: &Path) -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_google-hashcode-score-2022"))
+            .args(["serve", &path("res/a_an_example.in.txt"), "--port", "0"])
+            .arg("--leaderboard")
+            .arg(leaderboard_dir)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut line = String::new();
+        BufReader::new(child.stdout.as_mut().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let port = line
+            .trim()
+            .rsplit(':')
+            .next()
+            .and_then(|port| port.parse().ok())
+            .unwrap_or_else(|| panic!("unexpected first line {:?}", line));
+        Server { child, port }
+    }
+
+    /// Status code and body of the response, `headers` are sent as is.
+    fn request(&self, method: &str, url: &str, headers: &str, body: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(("127.0.0.1", self.port)).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n{}\r\n{}",
+            method, url, headers, body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        (status, body.to_string())
+    }
+
+    fn post(&self, url: &str, body: &str) -> (u16, String) {
+        let headers = format!("Content-Length: {}\r\n", body.len());
+        self.request("POST", url, &headers, body)
+    }
+
+    fn get_json(&self, url: &str) -> serde_json::Value {
+        let (status, body) = self.request("GET", url, "", "");
+        assert_eq!(status, 200, "{}", body);
+        serde_json::from_str(&body).unwrap()
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+const EXAMPLE_OUTPUT: &str = "3\nWebServer\nBob Anna\nLogging\nAnna\nWebChat\nMaria Bob\n";
+
+#[test]
+fn score_and_error_responses() {
+    let dir = test_dir("server");
+    let server = Server::start(&dir.join("leaderboard"));
+
+    let (status, body) = server.post("/score/a_an_example", EXAMPLE_OUTPUT);
+    assert_eq!(status, 200, "{}", body);
+    let report: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(report["score"], 33);
+
+    let (status, body) = server.post("/score/a_an_example", "1\nLogging\nBob\n");
+    assert_eq!(status, 400, "{}", body);
+    let error: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert!(
+        error["error"].as_str().unwrap().contains("Logging"),
+        "{}",
+        body
+    );
+
+    assert_eq!(server.post("/score/z_unknown", EXAMPLE_OUTPUT).0, 404);
+    assert_eq!(server.request("GET", "/score/a_an_example", "", "").0, 405);
+    assert_eq!(server.request("GET", "/nowhere", "", "").0, 404);
+    let (status, body) = server.post("/score/a_an_example", "\u{0}\u{ff}");
+    assert_eq!(status, 400, "{}", body);
+
+    // rejected from the announced length, before reading the body
+    let too_large = format!("Content-Length: {}\r\n", MAX_BODY_SIZE + 1);
+    let (status, body) = server.request("POST", "/score/a_an_example", &too_large, "");
+    assert_eq!(status, 413, "{}", body);
+
+    // submissions without a user are not recorded
+    assert_eq!(
+        server.get_json("/leaderboard/history"),
+        serde_json::json!([])
+    );
+    drop(server);
+    remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn concurrent_submissions() {
+    let dir = test_dir("server-concurrent");
+    let server = Server::start(&dir.join("leaderboard"));
+
+    thread::scope(|scope| {
+        for i in 0..8 {
+            let server = &server;
+            scope.spawn(move || {
+                let url = format!("/score/a_an_example?user=user{}", i % 2);
+                let (status, body) = server.post(&url, EXAMPLE_OUTPUT);
+                assert_eq!(status, 200, "{}", body);
+            });
+        }
+    });
+
+    let history = server.get_json("/leaderboard/history");
+    assert_eq!(history.as_array().unwrap().len(), 8);
+    let history = server.get_json("/leaderboard/history?user=user1");
+    assert_eq!(history.as_array().unwrap().len(), 4);
+    let standings = server.get_json("/leaderboard");
+    assert_eq!(standings.as_array().unwrap().len(), 2);
+    assert_eq!(standings[0]["total_score"], 33);
+    drop(server);
+    remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn workers_must_be_at_least_one() {
+    let output = Command::new(env!("CARGO_BIN_EXE_google-hashcode-score-2022"))
+        .args(["serve", &path("res/a_an_example.in.txt"), "--workers", "0"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--workers"));
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::path;
+use google_hashcode_score_2022::load::{load_output, load_precomputed};
+use google_hashcode_score_2022::score::compute_project_scores_precomputed;
+use google_hashcode_score_2022::slack::{
+    critical_chain, critical_chains, project_slacks, ProjectSlack,
+};
+
+fn slacks(input: &str, output: &str) -> Vec<ProjectSlack> {
+    let mut precomputed = load_precomputed(&path(input), false).unwrap();
+    let output = load_output(&path(output)).unwrap();
+    let project_scores =
+        compute_project_scores_precomputed(&mut precomputed, &output, false).unwrap();
+    project_slacks(&output, &project_scores)
+}
+
+#[test]
+fn example_slack() {
+    let slacks = slacks("res/a_an_example.in.txt", "out/a_an_example.in.txt.out");
+    let slack: Vec<(&str, i64)> = slacks.iter().map(|s| (s.name.as_str(), s.slack)).collect();
+    assert_eq!(
+        slack,
+        vec![("WebServer", 0), ("Logging", -7), ("WebChat", 3)]
+    );
+    // Logging waits for Anna, busy on WebServer
+    assert_eq!(critical_chain(&slacks, 1), vec![0, 1]);
+    assert_eq!(critical_chain(&slacks, 0), vec![0]);
+
+    let chains = critical_chains(&slacks);
+    assert_eq!(chains.len(), 1);
+    assert_eq!(chains[0].project, "Logging");
+    assert_eq!(chains[0].steps[1].waits_for.as_deref(), Some("Anna"));
+}
+
+#[test]
+fn chains_have_no_gaps() {
+    let slacks = slacks(
+        "res/f_find_great_mentors.in.txt",
+        "tests/golden/f_find_great_mentors.in.txt.out",
+    );
+    let chains = critical_chains(&slacks);
+    assert_eq!(chains.len(), slacks.iter().filter(|s| s.slack < 0).count());
+    for chain in chains {
+        assert_eq!(chain.steps[0].waits_for, None);
+        assert_eq!(chain.steps[0].start, 0);
+        for pair in chain.steps.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+        assert_eq!(chain.steps.last().unwrap().project, chain.project);
+    }
+}
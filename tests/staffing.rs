@@ -0,0 +1,146 @@
+mod common;
+
+use common::path;
+use google_hashcode_score_2022::generate::{generate, GeneratorConfig};
+use google_hashcode_score_2022::load::{load_output, load_precomputed};
+use google_hashcode_score_2022::parser::parse_input;
+use google_hashcode_score_2022::score::{precompute_from_input, simulate, Event, PreComputed};
+use google_hashcode_score_2022::staffing::{Candidate, StaffingIndex};
+
+/// Same rules as the scorer: level >= required, or one level short with a mentor.
+fn brute_force(
+    precomputed: &PreComputed,
+    project: usize,
+    role: usize,
+    day: usize,
+) -> Vec<Candidate> {
+    let (skill, required) = precomputed.projects[project].skills[role];
+    let mut candidates: Vec<Candidate> = precomputed
+        .contributors
+        .iter()
+        .filter(|c| c.next_availability <= day)
+        .filter_map(|c| {
+            let level = precomputed.levels.get(&(c.id, skill)).cloned().unwrap_or(0);
+            if level + 1 >= required {
+                Some(Candidate {
+                    contributor: c.id,
+                    level,
+                    next_availability: c.next_availability,
+                    needs_mentor: level < required,
+                })
+            } else {
+                None
+            }
+        })
+        .collect();
+    candidates.sort_by_key(|c| {
+        (
+            c.needs_mentor,
+            std::cmp::Reverse(c.level),
+            c.next_availability,
+            c.contributor,
+        )
+    });
+    candidates
+}
+
+#[test]
+fn candidates_match_brute_force() {
+    let input = generate(&GeneratorConfig {
+        seed: 7,
+        contributors: 60,
+        projects: 40,
+        skills: 8,
+        max_level: 4,
+        ..GeneratorConfig::default()
+    })
+    .unwrap();
+    let mut precomputed = precompute_from_input(&input);
+    for (i, contributor) in precomputed.contributors.iter_mut().enumerate() {
+        contributor.next_availability = i % 5;
+    }
+    let index = StaffingIndex::new(&precomputed);
+    for project in &precomputed.projects {
+        for role in 0..project.skills.len() {
+            for day in [0, 2, usize::MAX] {
+                assert_eq!(
+                    index
+                        .candidates(&precomputed, project.id, role, day)
+                        .unwrap(),
+                    brute_force(&precomputed, project.id, role, day),
+                    "project {} role {} day {}",
+                    project.name,
+                    role,
+                    day
+                );
+            }
+        }
+    }
+    assert!(index.candidates(&precomputed, 0, 100, 0).is_err());
+}
+
+#[test]
+fn level_0_and_level_1_roles() {
+    // Anna C++ 2, Bob HTML 1, Maria Python 1, P requires HTML 0 and C++ 1
+    let input = parse_input(
+        "3 1\nAnna 1\nC++ 2\nBob 1\nHTML 1\nMaria 1\nPython 1\nP 5 10 5 2\nHTML 0\nC++ 1\n",
+    )
+    .unwrap();
+    let precomputed = precompute_from_input(&input);
+    let index = StaffingIndex::new(&precomputed);
+    let candidate = |contributor, level, needs_mentor| Candidate {
+        contributor,
+        level,
+        next_availability: 0,
+        needs_mentor,
+    };
+    // everyone fills a level 0 role on their own
+    assert_eq!(
+        index.candidates(&precomputed, 0, 0, 0).unwrap(),
+        vec![
+            candidate(1, 1, false),
+            candidate(0, 0, false),
+            candidate(2, 0, false)
+        ]
+    );
+    // contributors without the skill need a mentor for a level 1 role
+    assert_eq!(
+        index.candidates(&precomputed, 0, 1, 0).unwrap(),
+        vec![
+            candidate(0, 2, false),
+            candidate(1, 0, true),
+            candidate(2, 0, true)
+        ]
+    );
+    for role in 0..2 {
+        assert_eq!(
+            index.candidates(&precomputed, 0, role, 0).unwrap(),
+            brute_force(&precomputed, 0, role, 0)
+        );
+    }
+}
+
+#[test]
+fn set_level_follows_level_ups() {
+    let precomputed = load_precomputed(&path("res/f_find_great_mentors.in.txt"), false).unwrap();
+    let output = load_output(&path("tests/golden/f_find_great_mentors.in.txt.out")).unwrap();
+    let mut index = StaffingIndex::new(&precomputed);
+    let mut state = precomputed.clone();
+    let mut previous_levels = precomputed.levels.clone();
+    simulate(&mut state, &output, false, |event| {
+        if let Event::LevelUp {
+            contributor,
+            skill,
+            level,
+            ..
+        } = event
+        {
+            let previous = previous_levels
+                .insert((*contributor, *skill), *level)
+                .unwrap_or(0);
+            index.set_level(*contributor, *skill, previous, *level);
+        }
+    })
+    .unwrap();
+    assert_eq!(index, StaffingIndex::new(&state));
+}
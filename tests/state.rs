@@ -0,0 +1,60 @@
+mod common;
+
+use common::path;
+use google_hashcode_score_2022::load::{load_output, load_precomputed};
+use google_hashcode_score_2022::state::{
+    contributors_state, replay_until, ContributorState, Until,
+};
+
+fn example_state(until: Until) -> Vec<ContributorState> {
+    let precomputed = load_precomputed(&path("res/a_an_example.in.txt"), false).unwrap();
+    let output = load_output(&path("out/a_an_example.in.txt.out")).unwrap();
+    let state = replay_until(&precomputed, &output, false, until).unwrap();
+    contributors_state(&state)
+}
+
+fn contributor(name: &str, next_availability: usize, levels: &[(&str, usize)]) -> ContributorState {
+    ContributorState {
+        name: name.to_string(),
+        next_availability,
+        levels: levels.iter().map(|(s, l)| (s.to_string(), *l)).collect(),
+    }
+}
+
+#[test]
+fn state_at_day() {
+    // WebServer (Bob, Anna) days 0-7, then Logging (Anna) 7-12 and WebChat (Maria, Bob) 7-17
+    assert_eq!(
+        example_state(Until::Day(5)),
+        vec![
+            contributor("Anna", 7, &[("C++", 2)]),
+            contributor("Bob", 7, &[("HTML", 5)]),
+            contributor("Maria", 0, &[("Python", 3)]),
+        ]
+    );
+    assert_eq!(
+        example_state(Until::Day(7)),
+        vec![
+            contributor("Anna", 12, &[("C++", 3)]),
+            contributor("Bob", 17, &[("HTML", 5)]),
+            contributor("Maria", 17, &[("Python", 3)]),
+        ]
+    );
+}
+
+#[test]
+fn state_after_projects() {
+    assert_eq!(
+        example_state(Until::Projects(2)),
+        vec![
+            contributor("Anna", 12, &[("C++", 4)]),
+            contributor("Bob", 7, &[("HTML", 5)]),
+            contributor("Maria", 0, &[("Python", 3)]),
+        ]
+    );
+    // whole output, same as the scorer state at the end
+    assert_eq!(
+        example_state(Until::Projects(3)),
+        example_state(Until::Day(17))
+    );
+}